@@ -0,0 +1,538 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Measure the spatial statistics of a set of points.
+//!
+//! These are the usual ways of checking the quality of a blue noise distribution: the
+//! [pair correlation function](pair_correlation), [Ripley's K](ripley_k) and [L](ripley_l)
+//! functions, the radially averaged [power spectrum](periodogram), and a few simple
+//! [measures of the spacing](metrics) of the points, along with a [check](verify) that they
+//! really are a Poisson disk distribution. Each takes the points and the [`Poisson`] whose space
+//! they fill, which gives the domain's size and volume; in a [periodic](Poisson::with_periodic)
+//! box distances wrap around. For points made elsewhere, [`estimate`] can work out a matching
+//! radius and domain.
+//!
+//! In a box that doesn't wrap around, pairs of points are weighted with Ohser's translation
+//! correction, so that pairs near the edges aren't undercounted. No correction is made for a
+//! [`Shape`], whose estimates fall off at larger distances as a result.
+//!
+//! ```
+//! use fast_poisson::{analysis, Poisson2D};
+//!
+//! let mut poisson = Poisson2D::new();
+//! poisson.with_dimensions([4.0, 4.0], 0.1).with_periodic(true);
+//! let points = poisson.generate();
+//!
+//! // No two points are closer than the radius, so g(r) is 0 below it
+//! for (r, g) in analysis::pair_correlation(&points, &poisson, 0.5, 50) {
+//!     println!("g({:.3}) = {:.3}", r, g);
+//! }
+//!
+//! // Blue noise has little power at low frequencies
+//! let spectrum = analysis::periodogram(&points, &poisson, 10.0);
+//! ```
+
+use super::{Float, Point, Poisson, Shape};
+use crate::elimination::Space;
+use crate::grid::{partition_point, Buckets};
+use crate::optimize;
+use crate::shape::{ball_volume, packing_density};
+use std::fmt;
+
+#[cfg(test)]
+mod tests;
+
+/// The radius and domain of a Poisson disk distribution matching a set of points
+///
+/// Returned by [`estimate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate<const N: usize> {
+    /// The largest radius that the points, other than a few stray pairs, satisfy
+    pub radius: Float,
+    /// The box the points fill, as a [`Shape::Cuboid`]
+    pub domain: Shape<N>,
+}
+
+impl<const N: usize> Estimate<N> {
+    /// A distribution with the estimated radius, filling the estimated domain
+    pub fn to_poisson(&self) -> Poisson<N> {
+        let mut poisson = Poisson::new();
+        poisson.with_shape(self.domain.clone(), self.radius);
+
+        poisson
+    }
+}
+
+/// Estimate the radius and domain of a Poisson disk distribution that matches the points
+///
+/// This is for points made elsewhere, such as imported from other tools, so that more can be
+/// added at the same density with [`Poisson::iter_from`], or a similar set generated.
+///
+/// The radius comes from the distance from each point to its nearest neighbor: it's the smallest
+/// of these, ignoring any less than half the median, which are taken to be strays. The domain
+/// is the points' bounding box, widened on each side by the gap expected between the outermost
+/// point and the edge of the space they were spread over.
+///
+/// Returns `None` if there are fewer than 2 points, or they're all in the same place.
+///
+/// ```
+/// use fast_poisson::{analysis, Poisson2D};
+///
+/// let mut poisson = Poisson2D::new();
+/// poisson.with_dimensions([10.0, 10.0], 0.5);
+/// let legacy = poisson.generate();
+///
+/// let estimate = analysis::estimate(&legacy).unwrap();
+/// assert!((estimate.radius - 0.5).abs() < 0.01);
+///
+/// // Add more points around them, in a larger space
+/// poisson.with_dimensions([20.0, 10.0], estimate.radius);
+/// let added: Vec<_> = poisson.iter_from(&legacy).collect();
+/// ```
+pub fn estimate<const N: usize>(points: &[Point<N>]) -> Option<Estimate<N>> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let mut min = points[0];
+    let mut max = points[0];
+    for point in points.iter() {
+        for i in 0..N {
+            min[i] = min[i].min(point[i]);
+            max[i] = max[i].max(point[i]);
+        }
+    }
+    let mut size = [0.0; N];
+    for i in 0..N {
+        size[i] = max[i] - min[i];
+    }
+
+    // Distances are kept squared, just as `verify` compares them, so the points are sure to pass
+    let mut bounds = Poisson::new();
+    bounds.with_shape(Shape::Cuboid { min, max }, 0.0);
+    let mut distances = optimize::nearest_distances_squared(&bounds, points);
+    distances.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let median = distances[distances.len() / 2];
+    let d_squared = distances.iter().copied().find(|&d| d >= median / 4.0)?;
+    let radius = radius_within(d_squared);
+    if radius.is_nan() || radius <= 0.0 {
+        return None;
+    }
+
+    // Were the points spread uniformly over a wider range, the outermost ones would be expected
+    // to fall short of its edges by 1 / (n + 1) of the range
+    for i in 0..N {
+        let gap = if size[i] > 0.0 {
+            size[i] / (points.len() + 1) as Float
+        } else {
+            radius / 2.0
+        };
+        min[i] -= gap;
+        max[i] += gap;
+    }
+
+    Some(Estimate {
+        radius,
+        domain: Shape::Cuboid { min, max },
+    })
+}
+
+/// The largest radius whose square is no more than `d_squared`
+///
+/// The square root is rounded to the nearest value, which can be just too large for two points
+/// exactly that far apart to satisfy it.
+fn radius_within(d_squared: Float) -> Float {
+    let mut radius = d_squared.sqrt();
+    while radius > 0.0 && radius.powi(2) > d_squared {
+        // Step down to the next smaller float
+        radius = Float::from_bits(radius.to_bits() - 1);
+    }
+
+    radius
+}
+
+/// A way in which a set of points fails to be a Poisson disk distribution
+///
+/// Returned by [`verify`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Violation {
+    /// The point at `index` lies outside of the distribution's space
+    OutOfBounds {
+        /// The index of the point
+        index: usize,
+    },
+    /// The points at `first` and `second` are closer together than the radius
+    TooClose {
+        /// The index of one point, the lower of the two
+        first: usize,
+        /// The index of the other point
+        second: usize,
+        /// The distance between them
+        distance: Float,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::OutOfBounds { index } => {
+                write!(f, "point {} is outside of the distribution's space", index)
+            }
+            Violation::TooClose {
+                first,
+                second,
+                distance,
+            } => write!(
+                f,
+                "points {} and {} are only {} apart, closer than the radius",
+                first, second, distance
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// Check that the points are a Poisson disk distribution in the `domain`'s space
+///
+/// Every point must lie within the space, just as generated points do, and no two points may be
+/// closer together than the radius. Pairs are found with a grid, rather than by comparing every
+/// point with every other.
+///
+/// Returns the first violation found: points outside the space are reported before pairs that
+/// are too close, and both in order of their indices.
+///
+/// ```
+/// use fast_poisson::{analysis, Poisson2D};
+///
+/// let poisson = Poisson2D::new();
+/// let mut points = poisson.generate();
+/// assert_eq!(analysis::verify(&points, &poisson), Ok(()));
+///
+/// points.push(points[0]);
+/// assert!(analysis::verify(&points, &poisson).is_err());
+/// ```
+pub fn verify<const N: usize>(points: &[Point<N>], domain: &Poisson<N>) -> Result<(), Violation> {
+    if let Some(index) = points.iter().position(|&point| !domain.contains(point)) {
+        return Err(Violation::OutOfBounds { index });
+    }
+
+    let mut grid = Buckets::new(
+        domain.bounds(),
+        domain.radius,
+        domain.period().is_some(),
+        points.len(),
+    );
+    for (idx, &point) in points.iter().enumerate() {
+        grid.insert(idx, point);
+    }
+
+    let r_squared = domain.radius.powi(2);
+    for (first, &point) in points.iter().enumerate() {
+        let mut lowest: Option<(usize, Float)> = None;
+        grid.for_each_near(point, domain.radius, |second| {
+            let d_squared = grid.distance_squared(point, points[second]);
+            if second > first
+                && d_squared < r_squared
+                && !matches!(lowest, Some((idx, _)) if idx < second)
+            {
+                lowest = Some((second, d_squared));
+            }
+        });
+
+        if let Some((second, d_squared)) = lowest {
+            return Err(Violation::TooClose {
+                first,
+                second,
+                distance: d_squared.sqrt(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Measures of how well a set of points is spread out
+///
+/// Returned by [`metrics`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// The number of points
+    pub count: usize,
+    /// The smallest distance between any two points; infinite with fewer than 2 points
+    pub min_distance: Float,
+    /// The radius of the largest ball, centered in the space, that holds no points
+    ///
+    /// See [`coverage_radius`].
+    pub coverage_radius: Float,
+    /// Lagae and Dutré's relative radius ρ: half of `min_distance`, relative to the largest it
+    /// could be for this many points
+    ///
+    /// Distributions with ρ between 0.65 and 0.85 are generally considered good; above that they
+    /// start to look regular.
+    pub relative_radius: Float,
+    /// The number of points relative to the most that could be packed into the space at the
+    /// distribution's radius
+    pub relative_density: Float,
+}
+
+/// Measure how well the points are spread out
+///
+/// The `domain`'s radius is only used for [`Metrics::relative_density`].
+pub fn metrics<const N: usize>(points: &[Point<N>], domain: &Poisson<N>) -> Metrics {
+    let space = Space::of(domain);
+    let min_distance = min_distance(points, domain);
+    let max_count = packing_density(N) * space.volume / ball_volume(N, domain.radius / 2.0);
+
+    Metrics {
+        count: points.len(),
+        min_distance,
+        coverage_radius: coverage_radius(points, domain),
+        relative_radius: min_distance / 2.0 / space.max_radius(points.len()),
+        relative_density: points.len() as Float / max_count,
+    }
+}
+
+/// The smallest distance between any two of the points
+///
+/// This is infinite if there are fewer than 2 points.
+pub fn min_distance<const N: usize>(points: &[Point<N>], domain: &Poisson<N>) -> Float {
+    optimize::min_distance(domain, points)
+}
+
+/// Estimate the radius of the largest ball, centered in the space, that holds none of the points
+///
+/// This is the furthest any position in the space is from its nearest point. It's found by
+/// searching around each point for the spot furthest from all of them, so the estimate is a lower
+/// bound: it may fall a little short of the true value but never exceeds it. The search is seeded
+/// the same way every time, whether or not the `domain` has a seed, so the same points always
+/// give the same estimate. A [maximal](Poisson::with_maximal) distribution has a coverage radius
+/// less than its radius.
+pub fn coverage_radius<const N: usize>(points: &[Point<N>], domain: &Poisson<N>) -> Float {
+    optimize::coverage_radius(domain, points)
+}
+
+/// Estimate the pair correlation function g(r) out to `max_distance`, in `bins` equal steps
+///
+/// Returns the distance at the middle of each bin along with the value of g there. g(r) is the
+/// density of points at distance r from a typical point, relative to uniformly random points;
+/// a Poisson disk distribution has g(r) = 0 for r less than its radius, a peak just beyond it,
+/// and g(r) close to 1 further away. In a periodic box, keep `max_distance` under half the box.
+pub fn pair_correlation<const N: usize>(
+    points: &[Point<N>],
+    domain: &Poisson<N>,
+    max_distance: Float,
+    bins: usize,
+) -> Vec<(Float, Float)> {
+    let width = max_distance / bins as Float;
+    let mut sums = vec![0.0; bins];
+    for_each_pair(points, domain, max_distance, |distance, weight| {
+        let bin = (distance / width) as usize;
+        if bin < bins {
+            sums[bin] += weight;
+        }
+    });
+
+    let scale = pair_scale(points.len(), domain);
+    sums.iter()
+        .enumerate()
+        .map(|(bin, &sum)| {
+            let (inner, outer) = (bin as Float * width, (bin + 1) as Float * width);
+            let shell = ball_volume(N, outer) - ball_volume(N, inner);
+            ((bin as Float + 0.5) * width, scale * sum / shell)
+        })
+        .collect()
+}
+
+/// Estimate Ripley's K function at each of the given distances
+///
+/// K(r) is the expected number of other points within r of a typical point, divided by the
+/// density of points. For uniformly random points it's the volume of a ball of radius r.
+pub fn ripley_k<const N: usize>(
+    points: &[Point<N>],
+    domain: &Poisson<N>,
+    radii: &[Float],
+) -> Vec<Float> {
+    let max_distance = radii.iter().copied().fold(0.0, Float::max);
+
+    // Total up the weights of the pairs in order of distance, to look up each radius in turn
+    let mut pairs = Vec::new();
+    for_each_pair(points, domain, max_distance, |distance, weight| {
+        pairs.push((distance, weight));
+    });
+    pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    let mut total = 0.0;
+    let cumulative: Vec<Float> = pairs
+        .iter()
+        .map(|&(_, weight)| {
+            total += weight;
+            total
+        })
+        .collect();
+
+    let scale = pair_scale(points.len(), domain);
+    radii
+        .iter()
+        .map(|&r| {
+            let within = partition_point(&pairs, |&(distance, _)| distance <= r);
+            match within {
+                0 => 0.0,
+                _ => scale * cumulative[within - 1],
+            }
+        })
+        .collect()
+}
+
+/// Estimate Ripley's L function at each of the given distances
+///
+/// L(r) is [K(r)](ripley_k) scaled back to a distance: the radius of the ball whose volume is
+/// K(r). For uniformly random points L(r) = r, so a distribution is more regular than random
+/// where L(r) < r.
+pub fn ripley_l<const N: usize>(
+    points: &[Point<N>],
+    domain: &Poisson<N>,
+    radii: &[Float],
+) -> Vec<Float> {
+    ripley_k(points, domain, radii)
+        .into_iter()
+        .map(|k| (k / ball_volume(N, 1.0)).powf(1.0 / N as Float))
+        .collect()
+}
+
+/// Compute the radially averaged periodogram, up to `max_frequency` cycles per unit of distance
+///
+/// The power at each frequency f is |Σ exp(-2πi f·x)|² / n over the points x; it averages 1 for
+/// uniformly random points, while blue noise has almost no power at low frequencies. The
+/// frequencies are those that fit a whole number of cycles across the distribution's bounding
+/// box, and are averaged in rings as wide as the lowest frequency, 1 / (the longest side of the
+/// box). Returns the frequency at the middle of each ring along with its average power, skipping
+/// any rings that hold no frequencies.
+///
+/// This takes time proportional to the number of points times the number of frequencies, which
+/// grows with `max_frequency` to the power of the number of dimensions.
+pub fn periodogram<const N: usize>(
+    points: &[Point<N>],
+    domain: &Poisson<N>,
+    max_frequency: Float,
+) -> Vec<(Float, Float)> {
+    let (origin, size) = domain.bounds();
+    let step = 1.0 / size.iter().copied().fold(0.0, Float::max);
+    let rings = (max_frequency / step).floor().max(0.0) as usize;
+    let tau = 2.0 * std::f64::consts::PI as Float;
+
+    // The range of whole numbers of cycles across the box along each dimension, enough to fill
+    // the outermost ring
+    let mut max_cycles = [0_isize; N];
+    for (m, dim) in max_cycles.iter_mut().zip(size.iter()) {
+        *m = ((rings as Float + 0.5) * step * dim).floor() as isize;
+    }
+
+    let mut sums = vec![0.0; rings + 1];
+    let mut counts = vec![0_usize; rings + 1];
+    let mut cycles = max_cycles;
+    cycles.iter_mut().for_each(|m| *m = -*m);
+    loop {
+        let mut frequency = [0.0; N];
+        for i in 0..N {
+            frequency[i] = cycles[i] as Float / size[i];
+        }
+        let magnitude = frequency.iter().map(|f| f * f).sum::<Float>().sqrt();
+        let ring = (magnitude / step).round() as usize;
+
+        // The zero frequency only counts the points, so it's left out
+        if ring > 0 && ring <= rings {
+            let (mut re, mut im) = (0.0, 0.0);
+            for point in points.iter() {
+                let phase = tau
+                    * (0..N)
+                        .map(|i| frequency[i] * (point[i] - origin[i]))
+                        .sum::<Float>();
+                re += phase.cos();
+                im -= phase.sin();
+            }
+            sums[ring] += (re * re + im * im) / points.len().max(1) as Float;
+            counts[ring] += 1;
+        }
+
+        // Advance to the next frequency, odometer-style
+        let mut i = 0;
+        loop {
+            if i == N {
+                return (1..=rings)
+                    .filter(|&ring| counts[ring] > 0)
+                    .map(|ring| (ring as Float * step, sums[ring] / counts[ring] as Float))
+                    .collect();
+            }
+            if cycles[i] < max_cycles[i] {
+                cycles[i] += 1;
+                break;
+            }
+            cycles[i] = -max_cycles[i];
+            i += 1;
+        }
+    }
+}
+
+/// Call `f` with the distance and weight of every pair of points up to `max_distance` apart
+///
+/// Each pair is visited once. The weight corrects for the pairs that would have straddled the
+/// edge of a box that doesn't wrap around.
+fn for_each_pair<const N: usize, F>(
+    points: &[Point<N>],
+    domain: &Poisson<N>,
+    max_distance: Float,
+    mut f: F,
+) where
+    F: FnMut(Float, Float),
+{
+    let (_, size) = domain.bounds();
+    let periodic = domain.period().is_some();
+    let corrected = domain.shape.is_none() && !periodic;
+
+    let mut grid = Buckets::new(domain.bounds(), max_distance, periodic, points.len());
+    for (idx, &point) in points.iter().enumerate() {
+        grid.insert(idx, point);
+    }
+
+    for (idx, &point) in points.iter().enumerate() {
+        grid.for_each_near(point, max_distance, |other| {
+            if other <= idx {
+                return;
+            }
+
+            let distance = grid.distance_squared(point, points[other]).sqrt();
+            if distance > max_distance {
+                return;
+            }
+
+            // A pair this far apart only fits in part of the box, so it stands in for the pairs
+            // that would have fallen outside it
+            let weight = if corrected {
+                (0..N)
+                    .map(|i| size[i] / (size[i] - (point[i] - points[other][i]).abs()))
+                    .product()
+            } else {
+                1.0
+            };
+
+            f(distance, weight);
+        });
+    }
+}
+
+/// The factor that turns a total of pair weights into a density relative to uniformly random
+/// points
+///
+/// Each pair counts for both of its points, and each of the n points has n - 1 others that
+/// could have been near it.
+fn pair_scale<const N: usize>(count: usize, domain: &Poisson<N>) -> Float {
+    if count < 2 {
+        return 0.0;
+    }
+
+    2.0 * domain.volume() / (count as Float * (count - 1) as Float)
+}
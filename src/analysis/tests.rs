@@ -0,0 +1,413 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+use crate::elimination::random_points;
+use crate::tests::largest_gap;
+use crate::{Poisson2D, Poisson3D, Shape};
+
+/// A large periodic box, and a Poisson disk distribution filling it
+fn blue_noise() -> (Poisson2D, Vec<Point<2>>) {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_dimensions([5.0, 5.0], 0.1)
+        .with_periodic(true)
+        .with_seed(17);
+    let points = poisson.generate();
+
+    (poisson, points)
+}
+
+/// Uniformly random points, as many as the blue noise has
+fn white_noise(periodic: bool) -> (Poisson2D, Vec<Point<2>>) {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_dimensions([5.0, 5.0], 0.1)
+        .with_periodic(periodic)
+        .with_seed(23);
+    let points = random_points(&poisson, 1500);
+
+    (poisson, points)
+}
+
+#[test]
+fn pair_correlation_of_blue_noise() {
+    let (poisson, points) = blue_noise();
+    let g = pair_correlation(&points, &poisson, 0.5, 25);
+    assert_eq!(g.len(), 25);
+
+    for &(r, value) in g.iter() {
+        if r < 0.1 {
+            // No two points are closer than the radius
+            assert_eq!(value, 0.0);
+        } else if r > 0.35 {
+            assert!((value - 1.0).abs() < 0.15, "g({}) = {}", r, value);
+        }
+    }
+    // There's a peak just beyond the radius
+    let peak = g
+        .iter()
+        .fold((0.0, 0.0), |a, &b| if b.1 > a.1 { b } else { a });
+    assert!(peak.0 > 0.1 && peak.0 < 0.2 && peak.1 > 1.2, "{:?}", peak);
+}
+
+#[test]
+fn pair_correlation_of_white_noise() {
+    for &periodic in [true, false].iter() {
+        let (poisson, points) = white_noise(periodic);
+        let g = pair_correlation(&points, &poisson, 1.0, 5);
+
+        for &(r, value) in g.iter() {
+            assert!((value - 1.0).abs() < 0.1, "g({}) = {}", r, value);
+        }
+    }
+}
+
+#[test]
+fn ripley_of_white_noise() {
+    let radii = [0.25, 0.5, 1.0];
+    for &periodic in [true, false].iter() {
+        let (poisson, points) = white_noise(periodic);
+        let k = ripley_k(&points, &poisson, &radii);
+        let l = ripley_l(&points, &poisson, &radii);
+
+        for i in 0..radii.len() {
+            let expected = ball_volume(2, radii[i]);
+            assert!(
+                (k[i] / expected - 1.0).abs() < 0.1,
+                "K({}) = {}",
+                radii[i],
+                k[i]
+            );
+            assert!(
+                (l[i] / radii[i] - 1.0).abs() < 0.05,
+                "L({}) = {}",
+                radii[i],
+                l[i]
+            );
+        }
+    }
+}
+
+#[test]
+fn ripley_of_blue_noise() {
+    let (poisson, points) = blue_noise();
+    let l = ripley_l(&points, &poisson, &[0.05, 0.1, 0.2]);
+
+    assert_eq!(l[0], 0.0);
+    // Points are spread more evenly than random, so there are fewer close neighbors
+    assert!(l[1] < 0.1);
+    assert!(l[2] < 0.2);
+}
+
+#[test]
+fn periodogram_of_blue_and_white_noise() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_dimensions([3.0, 3.0], 0.1)
+        .with_periodic(true)
+        .with_seed(17);
+    let points = poisson.generate();
+    let spectrum = periodogram(&points, &poisson, 20.0);
+    assert_eq!(spectrum.len(), 60);
+    assert!((spectrum[0].0 - 1.0 / 3.0).abs() < 1e-6);
+
+    let mean = |from: Float, to: Float| {
+        let ring: Vec<Float> = spectrum
+            .iter()
+            .filter(|s| s.0 >= from && s.0 < to)
+            .map(|s| s.1)
+            .collect();
+        ring.iter().sum::<Float>() / ring.len() as Float
+    };
+    // Almost no power at low frequencies, a peak around the spacing of the points, then about as
+    // much as random
+    assert!(mean(0.0, 3.0) < 0.15, "{}", mean(0.0, 3.0));
+    assert!(mean(7.0, 11.0) > 1.2, "{}", mean(7.0, 11.0));
+    assert!(
+        (mean(15.0, 20.0) - 1.0).abs() < 0.25,
+        "{}",
+        mean(15.0, 20.0)
+    );
+
+    poisson.with_seed(29);
+    let points = random_points(&poisson, points.len());
+    let spectrum = periodogram(&points, &poisson, 10.0);
+    let mean: Float = spectrum.iter().map(|s| s.1).sum::<Float>() / spectrum.len() as Float;
+    assert!((mean - 1.0).abs() < 0.2, "{}", mean);
+}
+
+#[test]
+fn too_few_points() {
+    let (poisson, _) = blue_noise();
+
+    assert!(pair_correlation(&[[1.0, 1.0]], &poisson, 0.5, 5)
+        .iter()
+        .all(|&(_, g)| g == 0.0));
+    assert_eq!(ripley_k(&[], &poisson, &[0.5]), vec![0.0]);
+    assert!(periodogram(&[], &poisson, 1.0)
+        .iter()
+        .all(|&(_, p)| p == 0.0));
+}
+
+#[test]
+fn metrics_of_blue_noise() {
+    for &periodic in [true, false].iter() {
+        let mut poisson = Poisson2D::new();
+        poisson.with_periodic(periodic).with_seed(31);
+        let points = poisson.generate();
+        let period = if periodic { Some([1.0, 1.0]) } else { None };
+
+        let m = metrics(&points, &poisson);
+        assert_eq!(m.count, points.len());
+
+        assert!((m.min_distance - crate::tests::min_distance(&points, period)).abs() < 1e-6);
+        assert!(m.min_distance >= 0.1);
+
+        // The search can't beat the true largest gap, but comes close to it; the probes are
+        // spaced 0.005 apart, so they may miss the true gap by up to half their diagonal
+        let gap = largest_gap(&points, [1.0, 1.0], period);
+        assert!(
+            m.coverage_radius < gap + 0.004,
+            "{} vs {}",
+            m.coverage_radius,
+            gap
+        );
+        assert!(
+            m.coverage_radius > gap * 0.97,
+            "{} vs {}",
+            m.coverage_radius,
+            gap
+        );
+        assert!(m.coverage_radius < 0.2);
+
+        assert!(m.relative_radius > 0.6 && m.relative_radius < 0.9);
+        assert!(m.relative_density > 0.4 && m.relative_density < 1.0);
+    }
+}
+
+#[test]
+fn metrics_of_maximal_distribution() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_maximal(true).with_seed(37);
+    let points = poisson.generate();
+
+    assert!(coverage_radius(&points, &poisson) < 0.1);
+    assert!(metrics(&points, &poisson).relative_density > relative_density_of(&poisson));
+}
+
+/// The relative density of an ordinary distribution in the same space
+fn relative_density_of(poisson: &Poisson2D) -> Float {
+    let mut poisson = poisson.clone();
+    poisson.with_maximal(false);
+    let points = poisson.generate();
+
+    metrics(&points, &poisson).relative_density
+}
+
+#[test]
+fn coverage_radius_is_repeatable() {
+    // Without a seed, the distribution's own random numbers would differ every time
+    let mut poisson = Poisson2D::new();
+    poisson.with_radius(0.05);
+    let points = poisson.generate();
+
+    let first = coverage_radius(&points, &poisson);
+    for _ in 0..3 {
+        assert_eq!(coverage_radius(&points, &poisson), first);
+    }
+}
+
+#[test]
+fn metrics_of_few_points() {
+    let (poisson, _) = blue_noise();
+
+    assert_eq!(min_distance(&[[1.0, 1.0]], &poisson), Float::INFINITY);
+    assert_eq!(coverage_radius(&[], &poisson), Float::INFINITY);
+    assert_eq!(metrics(&[], &poisson).count, 0);
+}
+
+#[test]
+fn verify_generated_points() {
+    let mut poisson = Poisson3D::new();
+    poisson.with_seed(41);
+    assert_eq!(verify(&poisson.generate(), &poisson), Ok(()));
+
+    poisson.with_periodic(true);
+    assert_eq!(verify(&poisson.generate(), &poisson), Ok(()));
+
+    poisson.with_shape(
+        Shape::Ball {
+            center: [0.0, 0.0, 0.0],
+            radius: 0.5,
+        },
+        0.1,
+    );
+    assert_eq!(verify(&poisson.generate(), &poisson), Ok(()));
+}
+
+#[test]
+fn verify_reports_violations() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(43);
+    let points = poisson.generate();
+
+    let mut outside = points.clone();
+    outside.insert(3, [0.5, 1.0]);
+    assert_eq!(
+        verify(&outside, &poisson),
+        Err(Violation::OutOfBounds { index: 3 })
+    );
+
+    let mut close = points.clone();
+    let near = [points[5][0] + 0.05, points[5][1]];
+    close.push(near);
+    let error = verify(&close, &poisson).unwrap_err();
+    match error {
+        Violation::TooClose {
+            first,
+            second,
+            distance,
+        } => {
+            assert_eq!(second, points.len());
+            assert!(first <= 5);
+            assert!(distance < 0.1);
+            assert!(error.to_string().contains("closer than the radius"));
+        }
+        _ => panic!("{:?}", error),
+    }
+}
+
+#[test]
+fn verify_wraps_around_periodic_box() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([1.0, 1.0], 0.1);
+    let points = [[0.01, 0.5], [0.98, 0.5]];
+    assert_eq!(verify(&points, &poisson), Ok(()));
+
+    poisson.with_periodic(true);
+    assert!(matches!(
+        verify(&points, &poisson),
+        Err(Violation::TooClose {
+            first: 0,
+            second: 1,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn estimate_generated_points() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_shape(
+            Shape::Cuboid {
+                min: [-1.0, 2.0],
+                max: [3.0, 4.0],
+            },
+            0.05,
+        )
+        .with_seed(47);
+    let points = poisson.generate();
+
+    let estimate = estimate(&points).unwrap();
+    assert!(estimate.radius >= 0.05 && estimate.radius < 0.0501);
+    match estimate.domain {
+        Shape::Cuboid { min, max } => {
+            for (found, expected) in min.iter().zip([-1.0, 2.0].iter()) {
+                assert!((found - expected).abs() < 0.005);
+            }
+            for (found, expected) in max.iter().zip([3.0, 4.0].iter()) {
+                assert!((found - expected).abs() < 0.005);
+            }
+        }
+        _ => panic!("{:?}", estimate.domain),
+    }
+
+    // The points fit in the distribution estimated from them
+    assert_eq!(verify(&points, &estimate.to_poisson()), Ok(()));
+}
+
+#[test]
+fn estimated_radius_is_never_rounded_up() {
+    for i in 1..10_000 {
+        let d_squared = i as Float * 0.0137;
+        let radius = radius_within(d_squared);
+        let next = Float::from_bits(radius.to_bits() + 1);
+
+        assert!(radius.powi(2) <= d_squared, "{}", d_squared);
+        assert!(next.powi(2) > d_squared, "{}", d_squared);
+    }
+
+    // √2 rounds up in double precision, so these points would fail against a rounded radius
+    let points = [[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]];
+    let estimate = estimate(&points).unwrap();
+    assert_eq!(verify(&points, &estimate.to_poisson()), Ok(()));
+}
+
+#[test]
+fn estimated_points_pass_verify() {
+    for seed in 0..20 {
+        let mut poisson = Poisson2D::new();
+        poisson
+            .with_dimensions([1.0, 1.0], 0.03 + seed as Float * 0.002)
+            .with_seed(seed);
+        let points = poisson.generate();
+
+        let estimate = estimate(&points).unwrap();
+        assert_eq!(verify(&points, &estimate.to_poisson()), Ok(()), "{}", seed);
+    }
+}
+
+#[test]
+fn estimate_ignores_strays() {
+    let mut poisson = Poisson3D::new();
+    poisson.with_seed(53);
+    let mut points = poisson.generate();
+    points.push([points[0][0] + 0.001, points[0][1], points[0][2]]);
+
+    let estimate = estimate(&points).unwrap();
+    assert!(
+        estimate.radius >= 0.1 && estimate.radius < 0.101,
+        "{}",
+        estimate.radius
+    );
+}
+
+#[test]
+fn estimate_needs_distinct_points() {
+    assert_eq!(estimate::<2>(&[]), None);
+    assert_eq!(estimate(&[[1.0, 1.0]]), None);
+    assert_eq!(estimate(&[[1.0, 1.0], [1.0, 1.0], [1.0, 1.0]]), None);
+
+    // Points in a line still get a domain with some width
+    let estimate = estimate(&[[0.0, 1.0], [1.0, 1.0], [2.0, 1.0]]).unwrap();
+    assert_eq!(estimate.radius, 1.0);
+    assert!(estimate.domain.volume() > 0.0);
+}
+
+#[test]
+fn extend_estimated_points() {
+    let mut legacy = Poisson2D::new();
+    legacy.with_dimensions([2.0, 2.0], 0.07).with_seed(59);
+    let left: Vec<_> = legacy.iter().filter(|p| p[0] < 1.0).collect();
+
+    let estimate = estimate(&left).unwrap();
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_dimensions([2.0, 2.0], estimate.radius)
+        .with_seed(61);
+    let added: Vec<_> = poisson.iter_from(&left).collect();
+
+    let mut all = left.clone();
+    all.extend(added.iter().copied());
+    assert_eq!(verify(&all, &poisson), Ok(()));
+
+    // The new half is about as dense as the old one
+    let right = all.iter().filter(|p| p[0] >= 1.0).count() as Float;
+    assert!((right / left.len() as Float - 1.0).abs() < 0.1);
+}
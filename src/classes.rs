@@ -0,0 +1,322 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Point, Poisson};
+use crate::grid::{wrap, Buckets};
+use crate::iter::{random_point_in, random_vector, step, Rand};
+use rand::prelude::*;
+#[cfg(feature = "derive_serde")]
+use serde::{Deserialize, Serialize};
+use std::iter::FusedIterator;
+
+#[cfg(test)]
+mod tests;
+
+/// The classes of points in a multi-class distribution, and how far apart they must be
+///
+/// Each class has its own radius, which keeps its points apart from each other, and a share of
+/// the points. Points of different classes are kept apart by the smaller of their two radii,
+/// unless set otherwise with [`Classes::with_spacing`]. A distribution takes its classes from
+/// [`Poisson::with_classes`].
+///
+/// ```
+/// # use fast_poisson::Classes;
+/// let mut classes = Classes::new();
+/// classes
+///     .with_class(0.2, 1.0) // trees
+///     .with_class(0.1, 2.0) // bushes
+///     .with_class(0.05, 1.0) // rocks
+///     .with_spacing(0, 1, 0.15); // bushes don't grow right under trees
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "derive_serde", derive(Serialize, Deserialize))]
+pub struct Classes {
+    /// The share of the points each class should get, relative to the others
+    shares: Vec<Float>,
+    /// The radius between points of each pair of classes
+    spacing: Vec<Vec<Float>>,
+}
+
+impl Classes {
+    /// Create an empty set of classes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a class whose points are at least `radius` apart
+    ///
+    /// Classes are numbered in the order they're added, starting from 0. The `share` is relative
+    /// to the shares of the other classes, so shares of 1 and 2 ask for twice as many points of
+    /// the second class as of the first. The distribution always puts its next point in
+    /// whichever class is furthest behind; once a class runs out of room, the others stop when
+    /// they reach their shares relative to it, even if there would be room for more.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `radius` is greater than 0, and `share` is finite and greater than 0.
+    pub fn with_class(&mut self, radius: Float, share: Float) -> &mut Self {
+        assert!(radius > 0.0, "The radius of a class must be greater than 0");
+        assert!(
+            share > 0.0 && share.is_finite(),
+            "The share of a class must be finite and greater than 0"
+        );
+
+        // Between classes, default to the smaller of the two radii
+        let mut row: Vec<Float> = self
+            .spacing
+            .iter_mut()
+            .enumerate()
+            .map(|(i, other)| {
+                let spacing = other[i].min(radius);
+                other.push(spacing);
+                spacing
+            })
+            .collect();
+        row.push(radius);
+        self.spacing.push(row);
+        self.shares.push(share);
+
+        self
+    }
+
+    /// Specify the radius between points of classes `i` and `j`
+    ///
+    /// # Panics
+    ///
+    /// Panics if either class doesn't exist, or unless `radius` is greater than 0.
+    pub fn with_spacing(&mut self, i: usize, j: usize, radius: Float) -> &mut Self {
+        assert!(
+            radius > 0.0,
+            "The spacing between classes must be greater than 0"
+        );
+
+        self.spacing[i][j] = radius;
+        self.spacing[j][i] = radius;
+
+        self
+    }
+
+    /// The number of classes
+    pub fn len(&self) -> usize {
+        self.shares.len()
+    }
+
+    /// Returns true if there are no classes
+    pub fn is_empty(&self) -> bool {
+        self.shares.is_empty()
+    }
+
+    /// The radius between points of classes `i` and `j`
+    ///
+    /// # Panics
+    ///
+    /// Panics if either class doesn't exist.
+    pub fn spacing(&self, i: usize, j: usize) -> Float {
+        self.spacing[i][j]
+    }
+
+    /// The largest radius between any two classes
+    fn max_spacing(&self) -> Float {
+        self.spacing
+            .iter()
+            .flatten()
+            .fold(0.0, |a: Float, &b| a.max(b))
+    }
+
+    /// The smallest radius between any two classes
+    fn min_spacing(&self) -> Float {
+        self.spacing
+            .iter()
+            .flatten()
+            .fold(Float::INFINITY, |a: Float, &b| a.min(b))
+    }
+}
+
+/// A point in a multi-class distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassPoint<const N: usize> {
+    /// The class the point belongs to
+    pub class: usize,
+    /// The position of the point
+    pub point: Point<N>,
+}
+
+/// An iterator over the points in a multi-class Poisson disk distribution
+///
+/// Created by [`Poisson::iter_classes`].
+pub struct ClassIter<const N: usize> {
+    /// The distribution whose space we're filling, and the classes to fill it with
+    distribution: Poisson<N>,
+    /// The RNG
+    rng: Rand,
+    /// The grid stores spatially-oriented samples for fast checking of neighboring sample points
+    grid: Buckets<N>,
+    /// Every point we've generated so far
+    points: Vec<ClassPoint<N>>,
+    /// How many points of each class we've generated
+    counts: Vec<usize>,
+    /// A list of valid points that we have not yet visited
+    active: Vec<usize>,
+    /// Whether each class has been given a last pass over all of the points
+    last_chance: Vec<bool>,
+    /// Whether each class has run out of room
+    full: Vec<bool>,
+    /// Whether we've placed the first point yet
+    started: bool,
+}
+
+impl<const N: usize> ClassIter<N> {
+    /// Create an iterator filling the distribution's space with its classes
+    pub(crate) fn new(distribution: &Poisson<N>) -> Self {
+        let classes = &distribution.classes;
+
+        let rng = distribution.rng();
+
+        // Expect about as many points as the closest spacing allows, to size the grid
+        let min_spacing = classes.min_spacing();
+        let expected = (distribution.volume() / min_spacing.powi(N as i32)).min(1e7) as usize;
+        let grid = Buckets::new(
+            distribution.bounds(),
+            min_spacing,
+            distribution.period().is_some(),
+            expected,
+        );
+
+        ClassIter {
+            distribution: distribution.clone(),
+            rng,
+            grid,
+            points: Vec::new(),
+            counts: vec![0; classes.len()],
+            active: Vec::new(),
+            last_chance: vec![false; classes.len()],
+            full: vec![false; classes.len()],
+            started: false,
+        }
+    }
+
+    /// The class that is furthest behind on its share, among those that still have room
+    fn neediest_class(&self) -> Option<usize> {
+        let behind =
+            |class: usize| self.counts[class] as Float / self.distribution.classes.shares[class];
+
+        // Once a class has run out of room, the others mustn't outgrow their shares relative to it
+        let cap = (0..self.distribution.classes.len())
+            .filter(|&class| self.full[class])
+            .map(behind)
+            .fold(Float::INFINITY, Float::min);
+
+        (0..self.distribution.classes.len())
+            .filter(|&class| !self.full[class] && behind(class) < cap)
+            .min_by(|&a, &b| {
+                // Among classes equally far behind, prefer those with larger shares
+                behind(a)
+                    .partial_cmp(&behind(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(
+                        self.distribution.classes.shares[b]
+                            .partial_cmp(&self.distribution.classes.shares[a])
+                            .unwrap_or(std::cmp::Ordering::Equal),
+                    )
+            })
+    }
+
+    /// Generate a random point between `radius` and `2 * radius` away from the given point
+    fn generate_random_point(&mut self, around: Point<N>, radius: Float) -> Point<N> {
+        let dist = radius * (1.0 + self.rng.gen::<Float>());
+        let point = step(around, random_vector(&mut self.rng), dist);
+
+        // In a periodic box, points that fall off one side come back in on the other
+        wrap(point, self.distribution.period())
+    }
+
+    /// Returns true if there is a point of any class too close to a new point of this class
+    fn in_neighborhood(&self, point: Point<N>, class: usize) -> bool {
+        let mut crowded = false;
+
+        self.grid
+            .for_each_near(point, self.distribution.classes.max_spacing(), |idx| {
+                let other = self.points[idx];
+                let radius = self.distribution.classes.spacing(class, other.class);
+                if self.grid.distance_squared(point, other.point) < radius.powi(2) {
+                    crowded = true;
+                }
+            });
+
+        crowded
+    }
+
+    /// Try to add a point of the given class, returning it if it fits
+    fn try_point(&mut self, point: Point<N>, class: usize) -> Option<ClassPoint<N>> {
+        if !self.distribution.contains(point) || self.in_neighborhood(point, class) {
+            return None;
+        }
+
+        let sample = ClassPoint { class, point };
+        let idx = self.points.len();
+        self.grid.insert(idx, point);
+        self.active.push(idx);
+        self.points.push(sample);
+        self.counts[class] += 1;
+
+        Some(sample)
+    }
+}
+
+impl<const N: usize> Iterator for ClassIter<N> {
+    type Item = ClassPoint<N>;
+
+    fn next(&mut self) -> Option<ClassPoint<N>> {
+        if !self.started {
+            // Start from a random point in our space
+            self.started = true;
+            let class = self.neediest_class()?;
+            let bounds = self.distribution.bounds();
+            for _ in 0..1000 {
+                let point = random_point_in(&mut self.rng, bounds);
+                if let Some(sample) = self.try_point(point, class) {
+                    return Some(sample);
+                }
+            }
+        }
+
+        loop {
+            while !self.active.is_empty() {
+                // Always add to whichever class is furthest behind, so the shares are kept
+                let class = self.neediest_class()?;
+                let i = self.rng.gen_range(0..self.active.len());
+                let around = self.points[self.active[i]];
+                let radius = self.distribution.classes.spacing(around.class, class);
+
+                for _ in 0..self.distribution.num_samples {
+                    let point = self.generate_random_point(around.point, radius);
+
+                    if let Some(sample) = self.try_point(point, class) {
+                        return Some(sample);
+                    }
+                }
+
+                self.active.swap_remove(i);
+            }
+
+            // The neediest class didn't fit around the last of the points; other classes may have
+            // been neediest when the rest were tried, so give it one more pass over all of them
+            // before letting the other classes carry on without it
+            let class = self.neediest_class()?;
+            if self.last_chance[class] {
+                self.full[class] = true;
+            }
+            self.last_chance[class] = true;
+            if self.points.is_empty() || self.neediest_class().is_none() {
+                return None;
+            }
+            self.active = (0..self.points.len()).collect();
+        }
+    }
+}
+
+impl<const N: usize> FusedIterator for ClassIter<N> {}
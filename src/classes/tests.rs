@@ -0,0 +1,113 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+use crate::Poisson2D;
+
+#[test]
+fn default_spacing_is_smaller_radius() {
+    let mut classes = Classes::new();
+    classes
+        .with_class(0.3, 1.0)
+        .with_class(0.1, 1.0)
+        .with_class(0.2, 1.0)
+        .with_spacing(0, 2, 0.25);
+
+    assert_eq!(classes.len(), 3);
+    assert_eq!(classes.spacing(1, 1), 0.1);
+    assert_eq!(classes.spacing(0, 1), 0.1);
+    assert_eq!(classes.spacing(2, 1), 0.1);
+    assert_eq!(classes.spacing(2, 0), 0.25);
+    assert_eq!(classes.spacing(0, 2), 0.25);
+}
+
+#[test]
+fn classes_are_spaced() {
+    let mut classes = Classes::new();
+    classes
+        .with_class(0.1, 1.0)
+        .with_class(0.05, 2.0)
+        .with_class(0.03, 1.0)
+        .with_spacing(0, 1, 0.08);
+
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_dimensions([2.0, 1.0], 0.0)
+        .with_classes(classes.clone())
+        .with_seed(0x5EED);
+    let points: Vec<_> = poisson.iter_classes().collect();
+
+    let mut counts = [0; 3];
+    for (i, a) in points.iter().enumerate() {
+        counts[a.class] += 1;
+        assert!(poisson.contains(a.point));
+        for b in &points[i + 1..] {
+            let dist = (a.point[0] - b.point[0]).hypot(a.point[1] - b.point[1]);
+            assert!(dist >= classes.spacing(a.class, b.class), "{:?} {:?}", a, b);
+        }
+    }
+
+    // Every class gets points, and the shares are roughly kept
+    assert!(counts.iter().all(|&count| count > 20), "{:?}", counts);
+    assert!(
+        counts[1] > counts[0] && counts[1] > counts[2],
+        "{:?}",
+        counts
+    );
+}
+
+#[test]
+fn classes_are_deterministic() {
+    let mut classes = Classes::new();
+    classes.with_class(0.1, 1.0).with_class(0.2, 1.0);
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_periodic(true)
+        .with_classes(classes)
+        .with_seed(9);
+
+    let first: Vec<_> = poisson.iter_classes().collect();
+    assert_eq!(first, poisson.iter_classes().collect::<Vec<_>>());
+}
+
+#[test]
+fn no_classes_no_points() {
+    assert_eq!(Poisson2D::new().iter_classes().count(), 0);
+}
+
+#[test]
+#[should_panic]
+fn class_radius_must_be_positive() {
+    Classes::new().with_class(0.0, 1.0);
+}
+
+#[test]
+#[should_panic]
+fn class_share_must_be_positive() {
+    Classes::new().with_class(0.1, 0.0);
+}
+
+#[test]
+#[should_panic]
+fn class_share_must_not_be_negative() {
+    Classes::new().with_class(0.1, -1.0);
+}
+
+#[test]
+#[should_panic]
+fn class_share_must_be_finite() {
+    Classes::new().with_class(0.1, Float::NAN);
+}
+
+#[test]
+#[should_panic]
+fn spacing_must_be_positive() {
+    Classes::new()
+        .with_class(0.1, 1.0)
+        .with_class(0.2, 1.0)
+        .with_spacing(0, 1, 0.0);
+}
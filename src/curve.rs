@@ -0,0 +1,412 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Point, Poisson, Spacing};
+use crate::grid::{partition_point, Buckets};
+use crate::iter::Rand;
+use crate::vector::{distance, normalize};
+use rand::prelude::*;
+use std::collections::HashMap;
+use std::iter::FusedIterator;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of straight pieces each Bézier segment is split into to measure its arc length
+const BEZIER_STEPS: usize = 64;
+
+/// A path through N-dimensional space: either a polyline or a cubic Bézier spline
+///
+/// ```
+/// # use fast_poisson::Curve;
+/// let path = Curve::polyline(&[[0.0, 0.0], [3.0, 0.0], [3.0, 4.0]]);
+///
+/// assert_eq!(path.length(), 7.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve<const N: usize> {
+    /// The vertices of the polyline, or the control points of the spline
+    points: Vec<Point<N>>,
+    /// Whether this is a spline rather than a polyline
+    bezier: bool,
+    /// Arc length table: the distance along the curve at which each (segment, t) sample lies
+    table: Vec<(Float, usize, Float)>,
+}
+
+impl<const N: usize> Curve<N> {
+    /// Create a curve that runs in straight lines from each point to the next
+    pub fn polyline(points: &[Point<N>]) -> Self {
+        let mut table = Vec::with_capacity(points.len());
+        let mut length = 0.0;
+
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                length += distance(points[i - 1], *point);
+            }
+            table.push((length, i, 0.0));
+        }
+
+        Curve {
+            points: points.to_vec(),
+            bezier: false,
+            table,
+        }
+    }
+
+    /// Create a spline of cubic Bézier segments
+    ///
+    /// The first segment runs from `control[0]` to `control[3]`, bending toward `control[1]` and
+    /// `control[2]`; each following segment picks up where the last one ended and uses the next 3
+    /// control points.
+    ///
+    /// ```
+    /// # use fast_poisson::Curve;
+    /// let arc = Curve::bezier(&[[0.0, 0.0], [0.0, 1.0], [1.0, 1.0], [1.0, 0.0]]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics unless there are 3k + 1 control points, for some k ≥ 1.
+    pub fn bezier(control: &[Point<N>]) -> Self {
+        assert!(
+            control.len() >= 4 && control.len() % 3 == 1,
+            "A cubic Bézier spline needs 3k + 1 control points"
+        );
+
+        let segments = (control.len() - 1) / 3;
+        let mut curve = Curve {
+            points: control.to_vec(),
+            bezier: true,
+            table: Vec::with_capacity(segments * BEZIER_STEPS + 1),
+        };
+
+        let mut length = 0.0;
+        let mut previous = control[0];
+        curve.table.push((0.0, 0, 0.0));
+        for segment in 0..segments {
+            for step in 1..=BEZIER_STEPS {
+                let t = step as Float / BEZIER_STEPS as Float;
+                let point = curve.evaluate(segment, t);
+
+                length += distance(previous, point);
+                previous = point;
+                curve.table.push((length, segment, t));
+            }
+        }
+
+        curve
+    }
+
+    /// The total length of the curve
+    pub fn length(&self) -> Float {
+        self.table.last().map_or(0.0, |&(length, _, _)| length)
+    }
+
+    /// The lowest corner and the size of a box holding the whole curve
+    ///
+    /// A Bézier segment never leaves the box around its control points, so this holds splines too.
+    fn bounds(&self) -> (Point<N>, [Float; N]) {
+        let first = self.points.first().copied().unwrap_or([0.0; N]);
+        let (mut min, mut max) = (first, first);
+        for point in &self.points {
+            for i in 0..N {
+                min[i] = min[i].min(point[i]);
+                max[i] = max[i].max(point[i]);
+            }
+        }
+
+        let mut size = [0.0; N];
+        for i in 0..N {
+            size[i] = max[i] - min[i];
+        }
+
+        (min, size)
+    }
+
+    /// Returns the point lying `arc_length` along the curve, and the unit tangent at that point
+    ///
+    /// The arc length is clamped to the curve. For splines the arc length is measured along a
+    /// finely subdivided approximation of the curve.
+    ///
+    /// ```
+    /// # use fast_poisson::Curve;
+    /// let path = Curve::polyline(&[[0.0, 0.0], [3.0, 0.0], [3.0, 4.0]]);
+    ///
+    /// assert_eq!(path.at(5.0), ([3.0, 2.0], [0.0, 1.0]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the curve has no points.
+    pub fn at(&self, arc_length: Float) -> (Point<N>, Point<N>) {
+        let arc_length = arc_length.clamp(0.0, self.length());
+        if self.table.len() == 1 {
+            // A polyline of a single point
+            return (self.points[0], [0.0; N]);
+        }
+
+        // Find the pair of entries in the arc length table either side of this arc length
+        let next = partition_point(&self.table, |&(length, _, _)| length <= arc_length)
+            .clamp(1, self.table.len() - 1);
+        let (s0, seg0, t0) = self.table[next - 1];
+        let (s1, seg1, t1) = self.table[next];
+        let fraction = if s1 > s0 {
+            (arc_length - s0) / (s1 - s0)
+        } else {
+            0.0
+        };
+
+        if self.bezier {
+            // The entry before the first of each segment is the end of the previous segment
+            let t0 = if seg0 == seg1 { t0 } else { 0.0 };
+            let t = t0 + (t1 - t0) * fraction;
+
+            (self.evaluate(seg1, t), normalize(self.derivative(seg1, t)))
+        } else {
+            let (a, b) = (self.points[seg0], self.points[seg1]);
+            let mut point = [0.0; N];
+            let mut tangent = [0.0; N];
+            for i in 0..N {
+                point[i] = a[i] + (b[i] - a[i]) * fraction;
+                tangent[i] = b[i] - a[i];
+            }
+
+            (point, normalize(tangent))
+        }
+    }
+
+    /// The point at `t` along a Bézier segment
+    fn evaluate(&self, segment: usize, t: Float) -> Point<N> {
+        let [p0, p1, p2, p3] = self.control(segment);
+        let u = 1.0 - t;
+
+        let mut point = [0.0; N];
+        for i in 0..N {
+            point[i] = u * u * u * p0[i]
+                + 3.0 * u * u * t * p1[i]
+                + 3.0 * u * t * t * p2[i]
+                + t * t * t * p3[i];
+        }
+
+        point
+    }
+
+    /// The derivative at `t` along a Bézier segment
+    fn derivative(&self, segment: usize, t: Float) -> Point<N> {
+        let [p0, p1, p2, p3] = self.control(segment);
+        let u = 1.0 - t;
+
+        let mut derivative = [0.0; N];
+        for i in 0..N {
+            derivative[i] = 3.0 * u * u * (p1[i] - p0[i])
+                + 6.0 * u * t * (p2[i] - p1[i])
+                + 3.0 * t * t * (p3[i] - p2[i]);
+        }
+
+        derivative
+    }
+
+    /// The control points of a Bézier segment
+    fn control(&self, segment: usize) -> [Point<N>; 4] {
+        let first = segment * 3;
+
+        [
+            self.points[first],
+            self.points[first + 1],
+            self.points[first + 2],
+            self.points[first + 3],
+        ]
+    }
+}
+
+/// A point on a [`Curve`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurvePoint<const N: usize> {
+    /// The position of the point
+    pub point: Point<N>,
+    /// The distance along the curve from its start to the point
+    pub arc_length: Float,
+    /// The unit tangent of the curve at the point, pointing toward the curve's end
+    pub tangent: Point<N>,
+}
+
+/// An iterator over the points in a Poisson disk distribution along a [`Curve`]
+///
+/// Created by [`Poisson::iter_curve`].
+pub struct CurveIter<'a, const N: usize> {
+    /// The curve we're sampling
+    curve: &'a Curve<N>,
+    /// How to measure the spacing between points
+    spacing: Spacing,
+    /// Radius around each point that must remain empty
+    radius: Float,
+    /// Number of samples to generate and test around each point
+    num_samples: u32,
+    /// The RNG
+    rng: Rand,
+    /// The grid stores spatially-oriented samples for fast checking of neighboring sample points
+    grid: Buckets<N>,
+    /// Every point we've generated so far, indexed by arc length in cells of size `radius`
+    arc_grid: HashMap<isize, usize>,
+    /// Every point we've generated so far
+    points: Vec<CurvePoint<N>>,
+    /// A list of valid points that we have not yet visited
+    active: Vec<usize>,
+    /// Whether we've placed the first point yet
+    started: bool,
+    /// How far along the curve we've swept looking for gaps the random samples missed
+    sweep: Float,
+}
+
+impl<'a, const N: usize> CurveIter<'a, N> {
+    /// Create an iterator along the curve
+    pub(crate) fn new(distribution: &Poisson<N>, curve: &'a Curve<N>, spacing: Spacing) -> Self {
+        // We maintain a grid of our samples for faster radius checking; without a positive radius
+        // we won't be placing any
+        let expected = if distribution.radius > 0.0 {
+            (curve.length() / distribution.radius) as usize + 1
+        } else {
+            1
+        };
+        let grid = Buckets::new(curve.bounds(), distribution.radius, false, expected);
+
+        let rng = distribution.rng();
+
+        CurveIter {
+            curve,
+            spacing,
+            radius: distribution.radius,
+            num_samples: distribution.num_samples,
+            rng,
+            grid,
+            arc_grid: HashMap::new(),
+            points: Vec::new(),
+            active: Vec::new(),
+            started: false,
+            sweep: 0.0,
+        }
+    }
+
+    /// Create the point lying `arc_length` along the curve
+    fn point_at(&self, arc_length: Float) -> CurvePoint<N> {
+        let (point, tangent) = self.curve.at(arc_length);
+
+        CurvePoint {
+            point,
+            arc_length,
+            tangent,
+        }
+    }
+
+    /// Returns true if there is at least one other sample point within `radius` of this point
+    fn in_neighborhood(&self, sample: &CurvePoint<N>) -> bool {
+        match self.spacing {
+            Spacing::Geodesic => {
+                let cell = (sample.arc_length / self.radius).floor() as isize;
+
+                (cell - 1..=cell + 1)
+                    .filter_map(|c| self.arc_grid.get(&c))
+                    .any(|&idx| {
+                        (self.points[idx].arc_length - sample.arc_length).abs() < self.radius
+                    })
+            }
+            Spacing::Euclidean => {
+                let r_squared = self.radius.powi(2);
+
+                let mut found = false;
+                self.grid.for_each_near(sample.point, self.radius, |idx| {
+                    found |= self
+                        .grid
+                        .distance_squared(self.points[idx].point, sample.point)
+                        < r_squared;
+                });
+
+                found
+            }
+        }
+    }
+
+    /// Add a point to our pattern
+    fn add_point(&mut self, sample: CurvePoint<N>) {
+        let idx = self.points.len();
+
+        self.grid.insert(idx, sample.point);
+        self.arc_grid
+            .insert((sample.arc_length / self.radius).floor() as isize, idx);
+        self.active.push(idx);
+        self.points.push(sample);
+    }
+
+    /// Try to add the point at `arc_length`, returning it if it's far enough from the others
+    fn try_point(&mut self, arc_length: Float) -> Option<CurvePoint<N>> {
+        if arc_length < 0.0 || arc_length > self.curve.length() {
+            return None;
+        }
+
+        let sample = self.point_at(arc_length);
+        if self.in_neighborhood(&sample) {
+            return None;
+        }
+
+        self.add_point(sample);
+        Some(sample)
+    }
+}
+
+impl<'a, const N: usize> Iterator for CurveIter<'a, N> {
+    type Item = CurvePoint<N>;
+
+    fn next(&mut self) -> Option<CurvePoint<N>> {
+        // Without a positive radius there's no end to the points we could fit, and no step to
+        // sweep the curve with
+        if self.curve.table.is_empty() || self.radius <= 0.0 || self.radius.is_nan() {
+            return None;
+        }
+        if !self.started {
+            // Start from a random point along the curve
+            self.started = true;
+            let start = self.rng.gen::<Float>() * self.curve.length();
+
+            return self.try_point(start);
+        }
+
+        while !self.active.is_empty() {
+            let i = self.rng.gen_range(0..self.active.len());
+            let around = self.points[self.active[i]].arc_length;
+
+            for _ in 0..self.num_samples {
+                // Step between radius and 2*radius along the curve, in either direction
+                let mut step = self.radius * (1.0 + self.rng.gen::<Float>());
+                if self.rng.gen::<bool>() {
+                    step = -step;
+                }
+
+                if let Some(sample) = self.try_point(around + step) {
+                    return Some(sample);
+                }
+            }
+
+            self.active.swap_remove(i);
+        }
+
+        // Where the curve doubles back on itself, random steps can miss gaps that are still wide
+        // enough for another point; sweep the curve to make sure we've found all of them
+        let step = self.radius / 4.0;
+        while self.sweep <= self.curve.length() {
+            let arc_length = self.sweep;
+            self.sweep += step;
+
+            if let Some(sample) = self.try_point(arc_length) {
+                return Some(sample);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, const N: usize> FusedIterator for CurveIter<'a, N> {}
@@ -0,0 +1,187 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+use crate::tests::{assert_spaced, min_distance};
+use crate::{Poisson, Poisson2D, Poisson3D};
+
+/// A hairpin: out along the X axis and back again, `gap` above it
+fn hairpin(gap: Float) -> Curve<2> {
+    Curve::polyline(&[[0.0, 0.0], [10.0, 0.0], [10.0, gap], [0.0, gap]])
+}
+
+#[test]
+fn polyline_length() {
+    assert_eq!(hairpin(1.0).length(), 21.0);
+    assert_eq!(Curve::<2>::polyline(&[[1.0, 1.0]]).length(), 0.0);
+    assert_eq!(Curve::<2>::polyline(&[]).length(), 0.0);
+}
+
+#[test]
+fn polyline_at() {
+    let curve = hairpin(1.0);
+
+    assert_eq!(curve.at(0.0), ([0.0, 0.0], [1.0, 0.0]));
+    assert_eq!(curve.at(10.5), ([10.0, 0.5], [0.0, 1.0]));
+    assert_eq!(curve.at(16.0), ([5.0, 1.0], [-1.0, 0.0]));
+    // Arc lengths are clamped to the curve
+    assert_eq!(curve.at(100.0), ([0.0, 1.0], [-1.0, 0.0]));
+    assert_eq!(curve.at(-1.0), curve.at(0.0));
+}
+
+#[test]
+fn straight_bezier_is_a_line() {
+    let curve = Curve::bezier(&[
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [2.0, 0.0, 0.0],
+        [3.0, 0.0, 0.0],
+    ]);
+
+    assert!((curve.length() - 3.0).abs() < 1e-6);
+    let (point, tangent) = curve.at(1.5);
+    assert!((point[0] - 1.5).abs() < 1e-6);
+    assert_eq!(tangent, [1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn bezier_spline_joins_segments() {
+    // Two quarter-ish circles of radius 1 making a half circle
+    let k = 0.552_284_8;
+    let curve = Curve::bezier(&[
+        [1.0, 0.0],
+        [1.0, k],
+        [k, 1.0],
+        [0.0, 1.0],
+        [-k, 1.0],
+        [-1.0, k],
+        [-1.0, 0.0],
+    ]);
+
+    let half_circle = std::f64::consts::PI as Float;
+    assert!((curve.length() - half_circle).abs() < 1e-3);
+
+    let (point, tangent) = curve.at(curve.length() / 2.0);
+    assert!(point[0].abs() < 1e-3 && (point[1] - 1.0).abs() < 1e-3);
+    assert!((tangent[0] + 1.0).abs() < 1e-3 && tangent[1].abs() < 1e-3);
+
+    for step in 0..=100 {
+        let (point, _) = curve.at(curve.length() * step as Float / 100.0);
+        let radius = point[0].hypot(point[1]);
+        assert!((radius - 1.0).abs() < 1e-3);
+    }
+}
+
+#[test]
+#[should_panic]
+fn bezier_needs_3k_plus_1_points() {
+    Curve::bezier(&[[0.0, 0.0], [1.0, 0.0], [2.0, 0.0], [3.0, 0.0], [4.0, 0.0]]);
+}
+
+#[test]
+fn arc_length_spacing() {
+    let curve = hairpin(0.5);
+    let mut poisson = Poisson2D::new();
+    poisson.with_radius(1.0).with_seed(1337);
+
+    let mut points: Vec<_> = poisson.iter_curve(&curve, Spacing::Geodesic).collect();
+    points.sort_by(|a, b| a.arc_length.partial_cmp(&b.arc_length).unwrap());
+
+    for pair in points.windows(2) {
+        let gap = pair[1].arc_length - pair[0].arc_length;
+        assert!(gap >= 1.0);
+        // The distribution is maximal: there's no room for another point between any two
+        assert!(gap < 2.0 + 0.25);
+    }
+    assert!(points[0].arc_length < 1.25);
+    assert!(points.last().unwrap().arc_length > curve.length() - 1.25);
+}
+
+#[test]
+fn euclidean_spacing_respects_bends() {
+    let curve = hairpin(0.5);
+    let mut poisson = Poisson2D::new();
+    poisson.with_radius(1.0).with_seed(1337);
+
+    let geodesic: Vec<_> = poisson.iter_curve(&curve, Spacing::Geodesic).collect();
+    let euclidean: Vec<_> = poisson.iter_curve(&curve, Spacing::Euclidean).collect();
+
+    let spaced: Vec<_> = euclidean.iter().map(|p| p.point).collect();
+    assert_spaced(&spaced, 1.0, None);
+    // Points on the two arms of the hairpin block each other, but only through space
+    assert!(euclidean.len() < geodesic.len() * 2 / 3);
+    let crowded: Vec<_> = geodesic.iter().map(|p| p.point).collect();
+    assert!(min_distance(&crowded, None) < 1.0);
+}
+
+#[test]
+fn euclidean_spacing_in_high_dimensions() {
+    // The arms of the hairpin are closer than the radius but 3 cells of radius / √5 apart
+    let mut turn = [0.0; 5];
+    turn[1] = 0.95;
+    let curve = Curve::polyline(&[
+        [0.0; 5],
+        [10.0, 0.0, 0.0, 0.0, 0.0],
+        [10.0, 0.95, 0.0, 0.0, 0.0],
+        turn,
+    ]);
+
+    for seed in 0..20 {
+        let mut poisson = Poisson::<5>::new();
+        poisson.with_radius(1.0).with_seed(seed);
+        let points: Vec<_> = poisson
+            .iter_curve(&curve, Spacing::Euclidean)
+            .map(|p| p.point)
+            .collect();
+
+        assert_spaced(&points, 1.0, None);
+    }
+}
+
+#[test]
+fn points_carry_curve_data() {
+    let curve = Curve::bezier(&[
+        [0.0, 0.0, 0.0],
+        [1.0, 2.0, 0.0],
+        [2.0, -2.0, 1.0],
+        [3.0, 0.0, 1.0],
+    ]);
+    let mut poisson = Poisson3D::new();
+    poisson.with_radius(0.2).with_seed(42);
+
+    for sample in poisson.iter_curve(&curve, Spacing::Euclidean) {
+        let (point, tangent) = curve.at(sample.arc_length);
+
+        assert_eq!(sample.point, point);
+        assert_eq!(sample.tangent, tangent);
+        assert!((tangent.iter().map(|t| t * t).sum::<Float>() - 1.0).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn degenerate_curves() {
+    let poisson = Poisson2D::new();
+
+    let empty = Curve::polyline(&[]);
+    assert_eq!(poisson.iter_curve(&empty, Spacing::Euclidean).count(), 0);
+
+    let dot = Curve::polyline(&[[0.5, 0.5]]);
+    let points: Vec<_> = poisson.iter_curve(&dot, Spacing::Euclidean).collect();
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].point, [0.5, 0.5]);
+}
+
+#[test]
+fn zero_radius_yields_nothing() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_radius(0.0);
+
+    let line = Curve::polyline(&[[0.0, 0.0], [1.0, 0.0]]);
+    for &spacing in &[Spacing::Euclidean, Spacing::Geodesic] {
+        assert_eq!(poisson.iter_curve(&line, spacing).count(), 0);
+    }
+}
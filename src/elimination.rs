@@ -0,0 +1,204 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Weighted sample elimination, after Cem Yuksel's ["Sample Elimination for Generating Poisson
+//! Disk Sample Sets"][Yuksel]
+//!
+//! [Yuksel]: http://www.cemyuksel.com/research/sampleelimination/
+
+use super::{Float, Point, Poisson};
+use crate::grid::{Buckets, Keyed};
+use crate::iter::random_point_in;
+use crate::shape::{ball_volume, packing_density};
+use std::collections::BinaryHeap;
+
+#[cfg(test)]
+mod tests;
+
+/// The exponent α of the weight function
+const ALPHA: i32 = 8;
+/// The exponent γ used to limit the weights of close points
+const GAMMA: Float = 1.5;
+/// The factor β used to limit the weights of close points
+const BETA: Float = 0.65;
+/// How many random candidates to generate for each point we want to keep
+pub(crate) const CANDIDATES_PER_POINT: usize = 5;
+
+/// The space in which points are eliminated
+pub(crate) struct Space<const N: usize> {
+    /// The lowest corner and the size of the box holding the space
+    pub(crate) bounds: (Point<N>, [Float; N]),
+    /// The volume of the space itself
+    pub(crate) volume: Float,
+    /// The size of the box if it wraps around
+    pub(crate) period: Option<[Float; N]>,
+}
+
+impl<const N: usize> Space<N> {
+    /// The space filled by a distribution
+    pub(crate) fn of(distribution: &Poisson<N>) -> Self {
+        Space {
+            bounds: distribution.bounds(),
+            volume: distribution.volume(),
+            period: distribution.period(),
+        }
+    }
+
+    /// The largest radius `count` points could have in this space, were they packed as densely
+    /// as possible
+    ///
+    /// This is half the distance between neighboring points in the densest packing.
+    pub(crate) fn max_radius(&self, count: usize) -> Float {
+        let volume = packing_density(N) * self.volume / count.max(1) as Float;
+
+        (volume / ball_volume(N, 1.0)).powf(1.0 / N as Float)
+    }
+}
+
+/// Choose `count` of the points to keep, eliminating the rest
+///
+/// Returns the indices of the points that are kept, in ascending order.
+pub(crate) fn eliminate<const N: usize>(
+    space: &Space<N>,
+    points: &[Point<N>],
+    count: usize,
+) -> Vec<usize> {
+    let mut order = elimination_order(space, points, count);
+    order.truncate(count);
+    order.sort_unstable();
+
+    order
+}
+
+/// Order the points so that every prefix is itself well spaced
+///
+/// Following Yuksel, the points are eliminated down to half as many, and those are eliminated
+/// down to half again, and so on; the points are then returned in the reverse of the order they
+/// were eliminated. Returns the indices of the points in their new order.
+pub(crate) fn progressive<const N: usize>(space: &Space<N>, points: &[Point<N>]) -> Vec<usize> {
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut eliminated = Vec::new();
+
+    while remaining.len() > 1 {
+        let subset: Vec<Point<N>> = remaining.iter().map(|&idx| points[idx]).collect();
+        let order = elimination_order(space, &subset, remaining.len() / 2);
+
+        // The last to be eliminated at each level come first within it
+        let (kept, level) = order.split_at(remaining.len() / 2);
+        eliminated.push(level.iter().map(|&i| remaining[i]).collect::<Vec<_>>());
+        remaining = kept.iter().map(|&i| remaining[i]).collect();
+    }
+
+    remaining.extend(eliminated.into_iter().rev().flatten());
+
+    remaining
+}
+
+/// Eliminate points until only `count` are left
+///
+/// Returns the indices of all of the points: first the `count` that are kept, and then the
+/// eliminated points in the reverse of the order they were eliminated.
+fn elimination_order<const N: usize>(
+    space: &Space<N>,
+    points: &[Point<N>],
+    count: usize,
+) -> Vec<usize> {
+    if count >= points.len() {
+        return (0..points.len()).collect();
+    }
+
+    // Points further apart than `d_max` don't affect each other, and points closer than `d_min`
+    // are counted as being `d_min` apart so a few tight clusters don't dominate the weights
+    let d_max = 2.0 * space.max_radius(count);
+    let ratio = count as Float / points.len() as Float;
+    let d_min = d_max * (1.0 - ratio.powf(GAMMA)) * BETA;
+    let weight = |dist_squared: Float| {
+        let dist = dist_squared.sqrt().max(d_min);
+
+        (1.0 - dist / d_max).powi(ALPHA)
+    };
+
+    let mut grid = Buckets::new(space.bounds, d_max, space.period.is_some(), points.len());
+    for (idx, &point) in points.iter().enumerate() {
+        grid.insert(idx, point);
+    }
+
+    // Every point starts out weighted by how crowded its neighborhood is
+    let d_max_squared = d_max.powi(2);
+    let mut weights = vec![0.0; points.len()];
+    for (idx, &point) in points.iter().enumerate() {
+        grid.for_each_near(point, d_max, |other| {
+            let dist_squared = grid.distance_squared(point, points[other]);
+            if other != idx && dist_squared < d_max_squared {
+                weights[idx] += weight(dist_squared);
+            }
+        });
+    }
+
+    let mut heap: BinaryHeap<Keyed> = weights
+        .iter()
+        .enumerate()
+        .map(|(idx, &weight)| Keyed(weight, idx))
+        .collect();
+    let mut alive = vec![true; points.len()];
+    let mut eliminated = Vec::with_capacity(points.len() - count);
+
+    // Repeatedly eliminate the most crowded point, and relieve its neighbors of its weight
+    while points.len() - eliminated.len() > count {
+        let Keyed(top, idx) = match heap.pop() {
+            Some(entry) => entry,
+            None => break,
+        };
+        if !alive[idx] || top != weights[idx] {
+            // This entry is out of date; a fresher one is still in the heap
+            continue;
+        }
+
+        alive[idx] = false;
+        eliminated.push(idx);
+        grid.remove(idx, points[idx]);
+
+        let point = points[idx];
+        grid.for_each_near(point, d_max, |other| {
+            let dist_squared = grid.distance_squared(point, points[other]);
+            if dist_squared < d_max_squared {
+                weights[other] -= weight(dist_squared);
+                heap.push(Keyed(weights[other], other));
+            }
+        });
+    }
+
+    (0..points.len())
+        .filter(|&idx| alive[idx])
+        .chain(eliminated.into_iter().rev())
+        .collect()
+}
+
+/// Generate uniformly random points filling the distribution's space
+pub(crate) fn random_points<const N: usize>(
+    distribution: &Poisson<N>,
+    count: usize,
+) -> Vec<Point<N>> {
+    let mut rng = distribution.rng();
+    let bounds = distribution.bounds();
+
+    // Shapes may fill only a small part of their bounding box, so allow plenty of misses; but
+    // don't keep trying forever if the space is empty
+    let mut points = Vec::with_capacity(count);
+    for _ in 0..count.saturating_mul(1000) {
+        if points.len() == count {
+            break;
+        }
+
+        let point = random_point_in(&mut rng, bounds);
+        if distribution.contains(point) {
+            points.push(point);
+        }
+    }
+
+    points
+}
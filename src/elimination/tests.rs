@@ -0,0 +1,85 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+use crate::tests::min_distance;
+use crate::{Poisson2D, Poisson3D, Shape};
+
+#[test]
+fn keeps_exactly_count() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(7);
+
+    for &count in &[1, 10, 100, 250] {
+        assert_eq!(poisson.generate_count(count).len(), count);
+    }
+}
+
+#[test]
+fn keeps_everything_when_too_few() {
+    let points = [[0.1, 0.1], [0.2, 0.2]];
+
+    assert_eq!(Poisson2D::new().eliminate(&points, 5), points.to_vec());
+}
+
+#[test]
+fn is_deterministic() {
+    let mut poisson = Poisson3D::new();
+    poisson.with_seed(0xC0FFEE);
+
+    assert_eq!(poisson.generate_count(200), poisson.generate_count(200));
+}
+
+#[test]
+fn spreads_points_out() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(1234);
+
+    let random = random_points(&poisson, 2000);
+    let points = poisson.eliminate(&random, 400);
+
+    // Yuksel reports minimum distances around 0.65 to 0.8 of the maximal radius
+    let r_max = Space::of(&poisson).max_radius(400);
+    assert!(min_distance(&points, None) > r_max);
+    assert!(min_distance(&points, None) > 4.0 * min_distance(&random, None));
+}
+
+#[test]
+fn respects_periodic_boundaries() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_periodic(true).with_seed(99);
+
+    let points = poisson.generate_count(300);
+    let r_max = Space::of(&poisson).max_radius(300);
+
+    assert!(min_distance(&points, Some([1.0, 1.0])) > r_max);
+}
+
+#[test]
+fn fills_shapes() {
+    let shape = Shape::Ball {
+        center: [0.0, 0.0],
+        radius: 1.0,
+    };
+    let mut poisson = Poisson2D::new();
+    poisson.with_shape(shape.clone(), 0.0).with_seed(5);
+
+    let points = poisson.generate_count(150);
+    assert_eq!(points.len(), 150);
+    assert!(points.iter().all(|&p| shape.contains(p)));
+}
+
+#[test]
+fn progressive_order_is_a_permutation() {
+    let mut poisson = Poisson3D::new();
+    poisson.with_seed(11);
+    let points = random_points(&poisson, 1000);
+
+    let mut order = progressive(&Space::of(&poisson), &points);
+    order.sort_unstable();
+    assert_eq!(order, (0..1000).collect::<Vec<_>>());
+}
@@ -0,0 +1,319 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Point};
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests;
+
+/// How far beyond the points the corners of the starting triangle are placed, relative to the size
+/// of the points' bounding box
+///
+/// The triangles touching these corners are dropped at the end, which can leave out a sliver
+/// where points on the convex hull are almost in a line; the further away they are, the thinner
+/// the slivers must be to go missing.
+const SUPER_TRIANGLE_SCALE: Float = 100.0;
+
+/// The Delaunay triangulation of a 2-dimensional distribution, and its dual Voronoi diagram
+///
+/// Returned by [`Poisson::triangulate`](crate::Poisson::triangulate).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangulation {
+    /// The points, in the order the distribution generated them
+    pub points: Vec<Point<2>>,
+    /// The indices of each triangle's corners, in counter-clockwise order
+    pub triangles: Vec<[usize; 3]>,
+    /// The lowest and highest corners of the box the Voronoi cells are clipped to
+    bounds: (Point<2>, Point<2>),
+    /// The points each point shares an edge with, including edges outside the convex hull
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl Triangulation {
+    /// Triangulate the points with the Bowyer–Watson algorithm
+    ///
+    /// `bounds` is the lowest corner and the size of the box to clip the Voronoi cells to, in the
+    /// same form as [`Poisson::bounds`](crate::Poisson::bounds).
+    pub(crate) fn new(points: Vec<Point<2>>, (origin, size): (Point<2>, [Float; 2])) -> Self {
+        let mut delaunay = Delaunay::new(&points);
+        for idx in 0..points.len() {
+            delaunay.insert(idx);
+        }
+
+        let n = points.len();
+        let mut triangles = Vec::new();
+        let mut adjacency = vec![Vec::new(); n];
+        for triangle in delaunay.triangles.iter() {
+            let v = triangle.vertices;
+            for i in 0..3 {
+                let (a, b) = (v[i], v[(i + 1) % 3]);
+                // Edges to the enclosing triangle's corners don't count, but the triangles
+                // touching them still link points along the convex hull
+                if a < n && b < n {
+                    adjacency[a].push(b);
+                }
+            }
+            if v.iter().all(|&idx| idx < n) {
+                triangles.push(v);
+            }
+        }
+        for links in adjacency.iter_mut() {
+            links.sort_unstable();
+            links.dedup();
+        }
+
+        let max = [origin[0] + size[0], origin[1] + size[1]];
+        Triangulation {
+            points,
+            triangles,
+            bounds: (origin, max),
+            adjacency,
+        }
+    }
+
+    /// The Voronoi cell around each point, clipped to the distribution's bounding box
+    ///
+    /// Each cell is a convex polygon, with its corners in counter-clockwise order; `cells[i]` is
+    /// the region closer to `points[i]` than to any other point. Together the cells tile the box.
+    pub fn voronoi_cells(&self) -> Vec<Vec<Point<2>>> {
+        let (min, max) = self.bounds;
+        let corners = vec![
+            [min[0], min[1]],
+            [max[0], min[1]],
+            [max[0], max[1]],
+            [min[0], max[1]],
+        ];
+
+        self.points
+            .iter()
+            .zip(self.adjacency.iter())
+            .map(|(&site, links)| {
+                links.iter().fold(corners.clone(), |cell, &other| {
+                    clip_closer(&cell, site, self.points[other])
+                })
+            })
+            .collect()
+    }
+}
+
+/// Clip a convex polygon to the half of the plane that is closer to `site` than to `other`
+fn clip_closer(polygon: &[Point<2>], site: Point<2>, other: Point<2>) -> Vec<Point<2>> {
+    // A point `x` is closer to `site` when `normal · x <= offset`
+    let normal = [other[0] - site[0], other[1] - site[1]];
+    let offset = (other[0].powi(2) + other[1].powi(2) - site[0].powi(2) - site[1].powi(2)) / 2.0;
+    let side = |x: Point<2>| normal[0] * x[0] + normal[1] * x[1] - offset;
+
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (i, &current) in polygon.iter().enumerate() {
+        let next = polygon[(i + 1) % polygon.len()];
+        let (a, b) = (side(current), side(next));
+
+        if a <= 0.0 {
+            clipped.push(current);
+        }
+        if (a < 0.0 && b > 0.0) || (a > 0.0 && b < 0.0) {
+            // The edge crosses the bisector
+            let t = a / (a - b);
+            clipped.push([
+                current[0] + t * (next[0] - current[0]),
+                current[1] + t * (next[1] - current[1]),
+            ]);
+        }
+    }
+
+    clipped
+}
+
+/// A triangle in the triangulation being built
+struct Triangle {
+    /// The indices of the corners, in counter-clockwise order
+    vertices: [usize; 3],
+    /// The triangle across the edge opposite each corner, if any
+    neighbors: [Option<usize>; 3],
+}
+
+/// An incremental Delaunay triangulation
+///
+/// The points are inserted one at a time within a triangle large enough to hold them all, whose
+/// corners are stored after them.
+struct Delaunay {
+    /// The points, followed by the corners of the enclosing triangle
+    coords: Vec<[Float; 2]>,
+    /// Every triangle so far; the new triangles around each point take over the slots of the ones
+    /// they replace
+    triangles: Vec<Triangle>,
+    /// The most recently created triangle, where the search for the next point starts
+    last: usize,
+    /// The point that last visited each triangle while looking for the cavity
+    visited: Vec<usize>,
+}
+
+impl Delaunay {
+    fn new(points: &[Point<2>]) -> Self {
+        let mut coords = points.to_vec();
+
+        let mut min: Point<2> = [0.0; 2];
+        let mut max: Point<2> = [0.0; 2];
+        if let Some(first) = coords.first() {
+            min = *first;
+            max = *first;
+        }
+        for p in coords.iter() {
+            for i in 0..2 {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            }
+        }
+        let center = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+        let size = (max[0] - min[0]).max(max[1] - min[1]).max(1.0) * SUPER_TRIANGLE_SCALE;
+
+        let n = coords.len();
+        coords.push([center[0] - size, center[1] - size]);
+        coords.push([center[0] + size, center[1] - size]);
+        coords.push([center[0], center[1] + size]);
+
+        Delaunay {
+            coords,
+            triangles: vec![Triangle {
+                vertices: [n, n + 1, n + 2],
+                neighbors: [None; 3],
+            }],
+            last: 0,
+            visited: vec![usize::MAX],
+        }
+    }
+
+    /// Add a point, replacing every triangle whose circumcircle holds it
+    fn insert(&mut self, idx: usize) {
+        let p = self.coords[idx];
+
+        // Find the cavity: the triangles around `p` whose circumcircles hold it
+        let start = self.locate(p);
+        self.visited[start] = idx;
+        let mut cavity = vec![start];
+        let mut i = 0;
+        while i < cavity.len() {
+            for nb in self.triangles[cavity[i]]
+                .neighbors
+                .iter()
+                .flatten()
+                .copied()
+            {
+                if self.visited[nb] != idx && self.in_circumcircle(nb, p) {
+                    self.visited[nb] = idx;
+                    cavity.push(nb);
+                }
+            }
+            i += 1;
+        }
+
+        // Connect each edge around the cavity to the new point
+        let mut edges = Vec::new();
+        for &t in cavity.iter() {
+            let triangle = &self.triangles[t];
+            for i in 0..3 {
+                let outside = triangle.neighbors[i];
+                if !matches!(outside, Some(nb) if self.visited[nb] == idx) {
+                    let v = triangle.vertices;
+                    edges.push((v[(i + 1) % 3], v[(i + 2) % 3], outside));
+                }
+            }
+        }
+
+        // Reuse the cavity's slots, then add the two extra triangles at the end
+        let mut slots = cavity.clone();
+        while slots.len() < edges.len() {
+            slots.push(self.triangles.len());
+            self.triangles.push(Triangle {
+                vertices: [0; 3],
+                neighbors: [None; 3],
+            });
+            self.visited.push(idx);
+        }
+
+        let mut starting_at = HashMap::with_capacity(edges.len());
+        let mut ending_at = HashMap::with_capacity(edges.len());
+        for (&(a, b, _), &slot) in edges.iter().zip(slots.iter()) {
+            starting_at.insert(a, slot);
+            ending_at.insert(b, slot);
+        }
+        for (&(a, b, outside), &slot) in edges.iter().zip(slots.iter()) {
+            self.triangles[slot] = Triangle {
+                vertices: [a, b, idx],
+                neighbors: [
+                    starting_at.get(&b).copied(),
+                    ending_at.get(&a).copied(),
+                    outside,
+                ],
+            };
+            if let Some(nb) = outside {
+                // The replaced triangle's slot may already hold a new one, so find the link by its edge
+                let neighbor = &mut self.triangles[nb];
+                let v = neighbor.vertices;
+                for j in 0..3 {
+                    if v[(j + 1) % 3] == b && v[(j + 2) % 3] == a {
+                        neighbor.neighbors[j] = Some(slot);
+                    }
+                }
+            }
+        }
+
+        self.last = slots[0];
+    }
+
+    /// Find the triangle holding `p` by walking toward it from the last one created
+    fn locate(&self, p: [Float; 2]) -> usize {
+        let mut t = self.last;
+        'walk: for _ in 0..self.triangles.len() {
+            let triangle = &self.triangles[t];
+            for i in 0..3 {
+                let a = self.coords[triangle.vertices[(i + 1) % 3]];
+                let b = self.coords[triangle.vertices[(i + 2) % 3]];
+                if orient(a, b, p) < 0.0 {
+                    if let Some(nb) = triangle.neighbors[i] {
+                        t = nb;
+                        continue 'walk;
+                    }
+                }
+            }
+
+            return t;
+        }
+
+        // The walk went in circles, which rounding can cause; fall back to checking every one
+        (0..self.triangles.len())
+            .find(|&t| {
+                let v = self.triangles[t].vertices;
+                (0..3).all(|i| {
+                    orient(self.coords[v[(i + 1) % 3]], self.coords[v[(i + 2) % 3]], p) >= 0.0
+                })
+            })
+            .unwrap_or(self.last)
+    }
+
+    /// Returns true if `p` lies strictly inside the triangle's circumcircle
+    fn in_circumcircle(&self, t: usize, p: [Float; 2]) -> bool {
+        let [a, b, c] = self.triangles[t].vertices;
+        let (a, b, c) = (self.coords[a], self.coords[b], self.coords[c]);
+
+        let (adx, ady) = (a[0] - p[0], a[1] - p[1]);
+        let (bdx, bdy) = (b[0] - p[0], b[1] - p[1]);
+        let (cdx, cdy) = (c[0] - p[0], c[1] - p[1]);
+
+        let det = (adx * adx + ady * ady) * (bdx * cdy - cdx * bdy)
+            + (bdx * bdx + bdy * bdy) * (cdx * ady - adx * cdy)
+            + (cdx * cdx + cdy * cdy) * (adx * bdy - bdx * ady);
+
+        det > 0.0
+    }
+}
+
+/// Twice the signed area of the triangle `abc`, positive when its corners are counter-clockwise
+fn orient(a: [Float; 2], b: [Float; 2], c: [Float; 2]) -> Float {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
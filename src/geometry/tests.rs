@@ -0,0 +1,157 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+use crate::grid::distance_squared;
+use crate::{Poisson2D, Shape};
+
+/// Signed area of a polygon, positive when counter-clockwise
+fn area(polygon: &[Point<2>]) -> Float {
+    (0..polygon.len())
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum::<Float>()
+        / 2.0
+}
+
+/// The convex hull of the points, with Andrew's monotone chain
+fn convex_hull(points: &[Point<2>]) -> Vec<Point<2>> {
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mut hull: Vec<Point<2>> = Vec::new();
+    for pass in 0..2 {
+        let start = hull.len();
+        for &p in points.iter() {
+            while hull.len() >= start + 2
+                && orient(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop();
+        if pass == 0 {
+            points.reverse();
+        }
+    }
+
+    hull
+}
+
+fn triangulate(seed: u64) -> Triangulation {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([3.0, 2.0], 0.1).with_seed(seed);
+
+    poisson.triangulate()
+}
+
+#[test]
+fn triangles_are_delaunay() {
+    let triangulation = triangulate(1);
+    let points = &triangulation.points;
+
+    for &corners in triangulation.triangles.iter() {
+        let [a, b, c] = corners;
+        let (a, b, c) = (points[a], points[b], points[c]);
+        assert!(orient(a, b, c) > 0.0);
+
+        // No point lies inside any triangle's circumcircle
+        let d = 2.0 * (a[0] * (b[1] - c[1]) + b[0] * (c[1] - a[1]) + c[0] * (a[1] - b[1]));
+        let sq = |p: Point<2>| p[0] * p[0] + p[1] * p[1];
+        let center = [
+            (sq(a) * (b[1] - c[1]) + sq(b) * (c[1] - a[1]) + sq(c) * (a[1] - b[1])) / d,
+            (sq(a) * (c[0] - b[0]) + sq(b) * (a[0] - c[0]) + sq(c) * (b[0] - a[0])) / d,
+        ];
+        let r_squared = distance_squared(center, a, None);
+        for (idx, &p) in points.iter().enumerate() {
+            if corners.contains(&idx) {
+                continue;
+            }
+            assert!(distance_squared(center, p, None) > r_squared * (1.0 - 1e-3));
+        }
+    }
+}
+
+#[test]
+fn triangles_cover_convex_hull() {
+    let triangulation = triangulate(2);
+    let points = &triangulation.points;
+
+    let covered: Float = triangulation
+        .triangles
+        .iter()
+        .map(|&[a, b, c]| area(&[points[a], points[b], points[c]]))
+        .sum();
+    let hull = area(&convex_hull(points));
+
+    assert!(
+        (covered - hull).abs() < hull * 1e-4,
+        "{} vs {}",
+        covered,
+        hull
+    );
+}
+
+#[test]
+fn voronoi_cells_tile_the_box() {
+    let triangulation = triangulate(3);
+    let cells = triangulation.voronoi_cells();
+    assert_eq!(cells.len(), triangulation.points.len());
+
+    let total: Float = cells.iter().map(|cell| area(cell)).sum();
+    assert!((total - 6.0).abs() < 1e-3, "{}", total);
+
+    for (cell, &site) in cells.iter().zip(triangulation.points.iter()) {
+        assert!(area(cell) > 0.0);
+        for &corner in cell.iter() {
+            assert!(corner[0] >= -1e-4 && corner[0] <= 3.0 + 1e-4);
+            assert!(corner[1] >= -1e-4 && corner[1] <= 2.0 + 1e-4);
+
+            // Each corner is as close to its own site as to any other
+            let own = distance_squared(corner, site, None);
+            assert!(triangulation
+                .points
+                .iter()
+                .all(|&p| distance_squared(corner, p, None) >= own * (1.0 - 1e-3) - 1e-6));
+        }
+    }
+}
+
+#[test]
+fn voronoi_cells_clip_to_shape_bounds() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_shape(
+            Shape::Ball {
+                center: [5.0, 5.0],
+                radius: 1.0,
+            },
+            0.2,
+        )
+        .with_seed(4);
+    let cells = poisson.triangulate().voronoi_cells();
+
+    let total: Float = cells.iter().map(|cell| area(cell)).sum();
+    assert!((total - 4.0).abs() < 1e-3, "{}", total);
+}
+
+#[test]
+fn few_points() {
+    let triangulation =
+        Triangulation::new(vec![[0.25, 0.5], [0.75, 0.5]], ([0.0, 0.0], [1.0, 1.0]));
+    assert!(triangulation.triangles.is_empty());
+
+    let cells = triangulation.voronoi_cells();
+    assert!((area(&cells[0]) - 0.5).abs() < 1e-6);
+    assert!(cells[0].iter().all(|p| p[0] <= 0.5 + 1e-6));
+
+    let empty = Triangulation::new(Vec::new(), ([0.0, 0.0], [1.0, 1.0]));
+    assert!(empty.triangles.is_empty() && empty.voronoi_cells().is_empty());
+}
@@ -0,0 +1,67 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Point, Poisson};
+use crate::grid::Buckets;
+
+#[cfg(test)]
+mod tests;
+
+/// A distribution's points, with every pair closer than some distance linked together
+///
+/// Returned by [`Poisson::neighbor_graph`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeighborGraph<const N: usize> {
+    /// The points, in the order the distribution generated them
+    pub points: Vec<Point<N>>,
+    /// The indices of each point's neighbors, in ascending order
+    pub neighbors: Vec<Vec<usize>>,
+}
+
+impl<const N: usize> NeighborGraph<N> {
+    /// Link every pair of points closer than `distance` to each other
+    pub(crate) fn new(distribution: &Poisson<N>, points: Vec<Point<N>>, distance: Float) -> Self {
+        let mut neighbors = vec![Vec::new(); points.len()];
+
+        if distance > 0.0 {
+            let mut grid = Buckets::new(
+                distribution.bounds(),
+                distance,
+                distribution.period().is_some(),
+                points.len(),
+            );
+            for (idx, &point) in points.iter().enumerate() {
+                grid.insert(idx, point);
+            }
+
+            let d_squared = distance.powi(2);
+            for (idx, &point) in points.iter().enumerate() {
+                let links = &mut neighbors[idx];
+                grid.for_each_near(point, distance, |other| {
+                    if other != idx && grid.distance_squared(point, points[other]) < d_squared {
+                        links.push(other);
+                    }
+                });
+                links.sort_unstable();
+            }
+        }
+
+        NeighborGraph { points, neighbors }
+    }
+
+    /// Every link between two points, as a pair of indices with the lower one first
+    ///
+    /// Each link is visited once.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors.iter().enumerate().flat_map(|(idx, links)| {
+            links
+                .iter()
+                .filter(move |&&other| other > idx)
+                .map(move |&other| (idx, other))
+        })
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::grid::distance_squared;
+use crate::{Float, Poisson2D, Poisson3D, Shape};
+
+/// Check the graph against every pair of points
+fn assert_links_all_close_pairs<const N: usize>(
+    graph: &crate::NeighborGraph<N>,
+    distance: Float,
+    period: Option<[Float; N]>,
+) {
+    for (i, &a) in graph.points.iter().enumerate() {
+        let expected: Vec<usize> = graph
+            .points
+            .iter()
+            .enumerate()
+            .filter(|&(j, &b)| j != i && distance_squared(a, b, period) < distance.powi(2))
+            .map(|(j, _)| j)
+            .collect();
+        assert_eq!(graph.neighbors[i], expected);
+    }
+}
+
+#[test]
+fn links_match_brute_force() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(4);
+    let graph = poisson.neighbor_graph(1.5);
+
+    assert_eq!(graph.points, poisson.generate());
+    assert_links_all_close_pairs(&graph, 0.15, None);
+    // Every point has somebody within 2r, so nobody is left out at that distance
+    assert!(poisson
+        .neighbor_graph(2.0)
+        .neighbors
+        .iter()
+        .all(|n| !n.is_empty()));
+}
+
+#[test]
+fn links_across_periodic_seam() {
+    let mut poisson = Poisson3D::new();
+    poisson
+        .with_dimensions([1.0, 1.0, 1.0], 0.2)
+        .with_periodic(true)
+        .with_seed(6);
+    let graph = poisson.neighbor_graph(1.8);
+
+    assert_links_all_close_pairs(&graph, 0.36, Some([1.0, 1.0, 1.0]));
+}
+
+#[test]
+fn links_in_shape() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_shape(
+            Shape::Ball {
+                center: [0.0, 0.0],
+                radius: 1.0,
+            },
+            0.1,
+        )
+        .with_seed(11);
+    let graph = poisson.neighbor_graph(1.6);
+
+    assert_links_all_close_pairs(&graph, 0.16, None);
+}
+
+#[test]
+fn edges_are_unique() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(9);
+    let graph = poisson.neighbor_graph(1.7);
+
+    let edges: Vec<_> = graph.edges().collect();
+    let links: usize = graph.neighbors.iter().map(Vec::len).sum();
+    assert_eq!(edges.len() * 2, links);
+    assert!(edges
+        .iter()
+        .all(|&(a, b)| a < b && graph.neighbors[b].contains(&a)));
+
+    // Points are at least the radius apart, so nothing is linked below that
+    assert_eq!(poisson.neighbor_graph(1.0).edges().count(), 0);
+    assert_eq!(poisson.neighbor_graph(0.0).edges().count(), 0);
+}
@@ -0,0 +1,271 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Point};
+use crate::iter::Cell;
+use std::cmp::Ordering;
+
+#[cfg(test)]
+mod tests;
+
+/// The most cells we'll allocate for each point we expect to store
+const MAX_CELLS_PER_POINT: usize = 4;
+
+/// A uniform grid of buckets for finding the points near any position
+///
+/// Unlike the grid in [`Iter`](crate::Iter), which relies on the points being at least `radius`
+/// apart to store only one point in each cell, any number of points can share a bucket. The
+/// points themselves are kept by the caller; the buckets only hold their indices.
+#[derive(Debug, Clone)]
+pub(crate) struct Buckets<const N: usize> {
+    /// The lowest corner of the box the grid covers
+    origin: Point<N>,
+    /// The size of the box the grid covers
+    extent: [Float; N],
+    /// Whether the box wraps around in every dimension
+    periodic: bool,
+    /// The size of the cells along each dimension; they evenly divide the box
+    cell_size: [Float; N],
+    /// The number of cells along each dimension
+    counts: [usize; N],
+    /// The indices of the points in each cell
+    cells: Vec<Vec<usize>>,
+}
+
+impl<const N: usize> Buckets<N> {
+    /// Create an empty grid over the box, with cells at least `cell_size` wide
+    ///
+    /// The cells are made larger if needed to keep the grid to a reasonable size for `expected`
+    /// points.
+    pub(crate) fn new(
+        (origin, extent): (Point<N>, [Float; N]),
+        cell_size: Float,
+        periodic: bool,
+        expected: usize,
+    ) -> Self {
+        let max_cells = expected.max(1).saturating_mul(MAX_CELLS_PER_POINT) as Float;
+        let mut min_size = cell_size.max(Float::MIN_POSITIVE);
+
+        // Grow the cells until the whole grid fits in our budget
+        let volume_cells = |size: Float| {
+            extent
+                .iter()
+                .map(|&e| (e / size).floor().max(1.0))
+                .product::<Float>()
+        };
+        while volume_cells(min_size) > max_cells {
+            min_size *= 1.5;
+        }
+
+        let mut counts = [1; N];
+        let mut sizes = [0.0; N];
+        for i in 0..N {
+            counts[i] = (extent[i] / min_size).floor().max(1.0) as usize;
+            sizes[i] = extent[i] / counts[i] as Float;
+        }
+        let total = counts.iter().product();
+
+        Buckets {
+            origin,
+            extent,
+            periodic,
+            cell_size: sizes,
+            counts,
+            cells: vec![Vec::new(); total],
+        }
+    }
+
+    /// Convert a point into grid cell coordinates, clamped to the grid
+    fn point_to_cell(&self, point: Point<N>) -> Cell<N> {
+        let mut cell = [0_isize; N];
+
+        for i in 0..N {
+            let c = if self.cell_size[i] > 0.0 {
+                ((point[i] - self.origin[i]) / self.cell_size[i]).floor() as isize
+            } else {
+                0
+            };
+            cell[i] = if self.periodic {
+                c.rem_euclid(self.counts[i] as isize)
+            } else {
+                c.clamp(0, self.counts[i] as isize - 1)
+            };
+        }
+
+        cell
+    }
+
+    /// Convert a cell into a grid vector index
+    fn cell_to_idx(&self, cell: Cell<N>) -> usize {
+        cell.iter()
+            .zip(self.counts.iter())
+            .fold(0, |acc, (&c, &count)| acc * count + c as usize)
+    }
+
+    /// Add the point with the given index
+    pub(crate) fn insert(&mut self, idx: usize, point: Point<N>) {
+        let cell = self.cell_to_idx(self.point_to_cell(point));
+
+        self.cells[cell].push(idx);
+    }
+
+    /// Remove the point with the given index, which must be at the same position it was added
+    pub(crate) fn remove(&mut self, idx: usize, point: Point<N>) {
+        let cell = self.cell_to_idx(self.point_to_cell(point));
+
+        if let Some(pos) = self.cells[cell].iter().position(|&i| i == idx) {
+            self.cells[cell].swap_remove(pos);
+        }
+    }
+
+    /// Call `f` with the index of every point that might be within `radius` of `point`
+    ///
+    /// Points further away may be visited too, so `f` needs to check the distance itself, e.g.
+    /// with [`Buckets::distance_squared`]. No point is visited twice.
+    pub(crate) fn for_each_near<F>(&self, point: Point<N>, radius: Float, mut f: F)
+    where
+        F: FnMut(usize),
+    {
+        let center = self.point_to_cell(point);
+
+        // The range of cells to visit along each dimension
+        let mut lo = [0_isize; N];
+        let mut hi = [0_isize; N];
+        for i in 0..N {
+            let count = self.counts[i] as isize;
+            let reach = if self.cell_size[i] > 0.0 {
+                (radius / self.cell_size[i]).ceil().min(count as Float) as isize
+            } else {
+                count
+            };
+
+            if self.periodic && 2 * reach + 1 >= count {
+                // The window wraps all the way around, so visit each cell once
+                lo[i] = 0;
+                hi[i] = count - 1;
+            } else if self.periodic {
+                lo[i] = center[i] - reach;
+                hi[i] = center[i] + reach;
+            } else {
+                lo[i] = (center[i] - reach).max(0);
+                hi[i] = (center[i] + reach).min(count - 1);
+            }
+        }
+
+        let mut cell = lo;
+        loop {
+            let mut wrapped = cell;
+            for i in 0..N {
+                wrapped[i] = cell[i].rem_euclid(self.counts[i] as isize);
+            }
+            for &idx in &self.cells[self.cell_to_idx(wrapped)] {
+                f(idx);
+            }
+
+            // Advance to the next cell, odometer-style
+            let mut i = 0;
+            loop {
+                if i == N {
+                    return;
+                }
+                if cell[i] < hi[i] {
+                    cell[i] += 1;
+                    break;
+                }
+                cell[i] = lo[i];
+                i += 1;
+            }
+        }
+    }
+
+    /// Squared distance between two points, taking the shortest way around a periodic box
+    pub(crate) fn distance_squared(&self, a: Point<N>, b: Point<N>) -> Float {
+        let period = if self.periodic {
+            Some(self.extent)
+        } else {
+            None
+        };
+
+        distance_squared(a, b, period)
+    }
+}
+
+/// Wrap a point around into the box of the given size, if there is one; otherwise leave it alone
+pub(crate) fn wrap<const N: usize>(mut point: Point<N>, period: Option<[Float; N]>) -> Point<N> {
+    if let Some(period) = period {
+        for (p, d) in point.iter_mut().zip(period.iter()) {
+            *p = p.rem_euclid(*d);
+            // Rounding can leave tiny negative values just on the wrong side of the seam
+            if *p >= *d {
+                *p = 0.0;
+            }
+        }
+    }
+
+    point
+}
+
+/// Squared distance between two points, taking the shortest way around the box of the given size
+/// if there is one
+pub(crate) fn distance_squared<const N: usize>(
+    a: Point<N>,
+    b: Point<N>,
+    period: Option<[Float; N]>,
+) -> Float {
+    (0..N)
+        .map(|i| {
+            let mut diff = (a[i] - b[i]).abs();
+            if let Some(period) = period {
+                diff = diff.min(period[i] - diff);
+            }
+            diff.powi(2)
+        })
+        .sum()
+}
+
+/// The number of items at the start of `sorted` for which `pred` holds
+///
+/// `pred` must hold for every item before the first one for which it doesn't. This is
+/// `partition_point`, which needs a newer compiler than we support.
+pub(crate) fn partition_point<T, P>(sorted: &[T], mut pred: P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    sorted
+        .binary_search_by(|item| {
+            if pred(item) {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        })
+        .unwrap_or_else(|idx| idx)
+}
+
+/// A priority queue entry holding an index, ordered by its key and then by the index
+///
+/// Floats aren't [`Ord`], so this gives [`BinaryHeap`](std::collections::BinaryHeap) a total
+/// order to work with; wrap it in [`Reverse`](std::cmp::Reverse) to pop the smallest key first.
+#[derive(PartialEq)]
+pub(crate) struct Keyed(pub(crate) Float, pub(crate) usize);
+
+impl Eq for Keyed {}
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .partial_cmp(&other.0)
+            .unwrap_or(Ordering::Equal)
+            .then(self.1.cmp(&other.1))
+    }
+}
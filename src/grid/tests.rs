@@ -0,0 +1,73 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+
+/// Collect the indices `for_each_near` visits
+fn near<const N: usize>(grid: &Buckets<N>, point: Point<N>, radius: Float) -> Vec<usize> {
+    let mut found = Vec::new();
+    grid.for_each_near(point, radius, |idx| found.push(idx));
+    found.sort_unstable();
+
+    found
+}
+
+#[test]
+fn finds_nearby_points() {
+    let points = [[0.05, 0.05], [0.15, 0.05], [0.95, 0.95], [0.5, 0.5]];
+    let mut grid = Buckets::new(([0.0; 2], [1.0; 2]), 0.1, false, points.len());
+    for (idx, &point) in points.iter().enumerate() {
+        grid.insert(idx, point);
+    }
+
+    let found = near(&grid, [0.1, 0.1], 0.1);
+    assert!(found.contains(&0) && found.contains(&1));
+    assert!(!found.contains(&2));
+
+    grid.remove(1, points[1]);
+    assert!(!near(&grid, [0.1, 0.1], 0.1).contains(&1));
+}
+
+#[test]
+fn wraps_when_periodic() {
+    let points = [[0.02, 0.5], [0.98, 0.5]];
+    let mut grid = Buckets::new(([0.0; 2], [1.0; 2]), 0.1, true, 100);
+    for (idx, &point) in points.iter().enumerate() {
+        grid.insert(idx, point);
+    }
+
+    assert_eq!(near(&grid, [0.0, 0.5], 0.1), vec![0, 1]);
+    assert!((grid.distance_squared(points[0], points[1]) - 0.04 * 0.04).abs() < 1e-6);
+}
+
+#[test]
+fn visits_each_point_once() {
+    // A radius larger than the whole box must not visit wrapped cells twice
+    let mut grid = Buckets::new(([0.0; 3], [1.0; 3]), 0.3, true, 10);
+    grid.insert(0, [0.5; 3]);
+
+    assert_eq!(near(&grid, [0.1; 3], 5.0), vec![0]);
+}
+
+#[test]
+fn minimal_image_distance() {
+    let period = Some([1.0, 2.0]);
+
+    assert!((distance_squared([0.1, 0.0], [0.9, 0.0], None) - 0.64).abs() < 1e-6);
+    assert!((distance_squared([0.1, 0.0], [0.9, 0.0], period) - 0.04).abs() < 1e-6);
+    assert!((distance_squared([0.0, 0.25], [0.0, 1.75], period) - 0.25).abs() < 1e-6);
+}
+
+#[test]
+fn partition_point_counts_leading_matches() {
+    let sorted = [0.1, 0.2, 0.2, 0.5];
+
+    assert_eq!(partition_point(&sorted, |&x| x <= 0.0), 0);
+    assert_eq!(partition_point(&sorted, |&x| x <= 0.2), 3);
+    assert_eq!(partition_point(&sorted, |&x| x <= 1.0), 4);
+    assert_eq!(partition_point(&[] as &[Float], |&x| x <= 1.0), 0);
+}
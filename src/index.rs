@@ -0,0 +1,125 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Iter, Point};
+use std::cmp::Ordering;
+
+#[cfg(test)]
+mod tests;
+
+/// The points of a finished distribution, indexed for finding the points near any position
+///
+/// This keeps the grid that was built to generate the distribution, so there's no need to build
+/// a k-d tree or similar over the output. Each query only visits the grid cells near the position
+/// it asks about. In a periodic box distances are measured the shortest way around.
+///
+/// Build one with [`Poisson::index`](crate::Poisson::index) or [`Iter::into_index`].
+///
+/// ```
+/// # use fast_poisson::Poisson2D;
+/// let index = Poisson2D::new().with_seed(7).index();
+///
+/// let nearest = index.nearest([0.5, 0.5]).unwrap();
+/// let neighbors = index.within_radius(nearest, 0.15);
+/// let closest_three = index.k_nearest([0.25, 0.75], 3);
+/// ```
+pub struct SpatialIndex<const N: usize> {
+    /// The iterator that generated the points, which holds them in its grid
+    iter: Iter<N>,
+    /// The distribution's radius, which bounds how far it is to the nearest point
+    radius: Float,
+}
+
+impl<const N: usize> SpatialIndex<N> {
+    /// Wrap an iterator that has finished generating its points
+    pub(crate) fn new(iter: Iter<N>, radius: Float) -> Self {
+        SpatialIndex { iter, radius }
+    }
+
+    /// The number of points in the index
+    pub fn len(&self) -> usize {
+        self.iter.points().count()
+    }
+
+    /// Returns true if there are no points in the index
+    pub fn is_empty(&self) -> bool {
+        self.iter.points().next().is_none()
+    }
+
+    /// An iterator over the points in the index
+    ///
+    /// The points are in no particular order.
+    pub fn points(&self) -> impl Iterator<Item = Point<N>> + '_ {
+        self.iter.points()
+    }
+
+    /// The point closest to the given position, or `None` if the index is empty
+    pub fn nearest(&self, point: Point<N>) -> Option<Point<N>> {
+        self.k_nearest(point, 1).into_iter().next()
+    }
+
+    /// Every point within `radius` of the given position, closest first
+    pub fn within_radius(&self, point: Point<N>, radius: Float) -> Vec<Point<N>> {
+        self.within_by_distance(point, radius)
+            .into_iter()
+            .map(|(_, point2)| point2)
+            .collect()
+    }
+
+    /// The `k` points closest to the given position, closest first
+    ///
+    /// Fewer than `k` points are returned only if there aren't that many in the index.
+    pub fn k_nearest(&self, point: Point<N>, k: usize) -> Vec<Point<N>> {
+        self.k_nearest_by_distance(point, k)
+            .into_iter()
+            .map(|(_, point2)| point2)
+            .collect()
+    }
+
+    /// Like [`SpatialIndex::within_radius`], with the squared distance to each point
+    fn within_by_distance(&self, point: Point<N>, radius: Float) -> Vec<(Float, Point<N>)> {
+        if radius.is_nan() || radius < 0.0 {
+            return Vec::new();
+        }
+        // No point is further away than this, and a larger radius would only search empty cells
+        let radius = radius.min(self.iter.max_distance(point));
+
+        let mut points: Vec<(Float, Point<N>)> = self
+            .iter
+            .within(point, radius)
+            .into_iter()
+            .map(|point2| (self.iter.distance_squared(point, point2), point2))
+            .collect();
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+        points
+    }
+
+    /// Like [`SpatialIndex::k_nearest`], with the squared distance to each point
+    pub(crate) fn k_nearest_by_distance(
+        &self,
+        point: Point<N>,
+        k: usize,
+    ) -> Vec<(Float, Point<N>)> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // Nearly every position in the space is within `2 * radius` of some point, so start there
+        // and widen the search until it finds enough, or takes in the whole grid
+        let furthest = self.iter.max_distance(point);
+        let mut radius = 2.0 * self.radius;
+        loop {
+            let mut points = self.within_by_distance(point, radius);
+            if points.len() >= k || radius >= furthest {
+                points.truncate(k);
+                return points;
+            }
+            radius *= 2.0;
+        }
+    }
+}
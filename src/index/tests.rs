@@ -0,0 +1,161 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::grid::distance_squared;
+use crate::{Float, Point, Poisson2D, Poisson3D};
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256StarStar;
+
+/// The `k` nearest points, found the slow way
+fn brute_force<const N: usize>(
+    points: &[Point<N>],
+    query: Point<N>,
+    k: usize,
+    period: Option<[Float; N]>,
+) -> Vec<Point<N>> {
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| {
+        distance_squared(query, *a, period)
+            .partial_cmp(&distance_squared(query, *b, period))
+            .unwrap()
+    });
+    points.truncate(k);
+
+    points
+}
+
+/// Random positions, including some outside the unit box
+fn queries<const N: usize>(count: usize) -> Vec<Point<N>> {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(99);
+
+    (0..count)
+        .map(|_| {
+            let mut point = [0.0; N];
+            for p in point.iter_mut() {
+                *p = rng.gen_range(-0.5..1.5);
+            }
+            point
+        })
+        .collect()
+}
+
+#[test]
+fn index_holds_the_distribution() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(5);
+    let index = poisson.index();
+
+    let mut points: Vec<_> = index.points().collect();
+    let mut generated = poisson.generate();
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    generated.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(points, generated);
+    assert_eq!(index.len(), generated.len());
+    assert!(!index.is_empty());
+}
+
+#[test]
+fn queries_match_brute_force() {
+    let mut poisson = Poisson3D::new();
+    poisson.with_dimensions([1.0, 1.0, 1.0], 0.15).with_seed(8);
+    let index = poisson.index();
+    let points = poisson.generate();
+
+    for query in queries::<3>(50) {
+        let nearest = brute_force(&points, query, 1, None);
+        assert_eq!(index.nearest(query), nearest.first().copied());
+
+        let dist = |p: &Point<3>| distance_squared(query, *p, None);
+        let knn = index.k_nearest(query, 7);
+        let expected = brute_force(&points, query, 7, None);
+        // Ties could come out in either order, but the distances must match
+        assert_eq!(
+            knn.iter().map(dist).collect::<Vec<_>>(),
+            expected.iter().map(dist).collect::<Vec<_>>()
+        );
+
+        let near = index.within_radius(query, 0.3);
+        let expected: Vec<_> = points.iter().filter(|p| dist(p) <= 0.09).collect();
+        assert_eq!(near.len(), expected.len());
+        assert!(near.windows(2).all(|w| dist(&w[0]) <= dist(&w[1])));
+    }
+}
+
+#[test]
+fn periodic_queries_wrap_around() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_periodic(true).with_seed(13);
+    let index = poisson.index();
+    let points = poisson.generate();
+    let period = Some([1.0, 1.0]);
+
+    for query in [[0.0, 0.0], [0.99, 0.01], [0.5, 0.999]].iter() {
+        let dist = |p: &Point<2>| distance_squared(*query, *p, period);
+        let knn = index.k_nearest(*query, 5);
+        let expected = brute_force(&points, *query, 5, period);
+        assert_eq!(
+            knn.iter().map(dist).collect::<Vec<_>>(),
+            expected.iter().map(dist).collect::<Vec<_>>()
+        );
+    }
+
+    // A search wider than the box finds every point exactly once
+    assert_eq!(index.within_radius([0.5, 0.5], 2.0).len(), points.len());
+}
+
+#[test]
+fn k_larger_than_index() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([1.0, 1.0], 0.4).with_seed(2);
+    let index = poisson.index();
+
+    assert_eq!(index.k_nearest([10.0, -10.0], 1000).len(), index.len());
+    assert!(index.k_nearest([0.5, 0.5], 0).is_empty());
+    assert!(index.within_radius([0.5, 0.5], -1.0).is_empty());
+}
+
+#[test]
+fn huge_radius_takes_in_everything() {
+    let mut poisson = Poisson3D::new();
+    poisson.with_dimensions([1.0, 1.0, 1.0], 0.2).with_seed(4);
+    let index = poisson.index();
+
+    for &radius in [1e3, 1e30, Float::INFINITY].iter() {
+        assert_eq!(
+            index.within_radius([0.5, 0.5, 0.5], radius).len(),
+            index.len()
+        );
+    }
+    assert!(index.within_radius([1e6, -1e6, 0.0], 1e3).is_empty());
+    assert_eq!(
+        index.within_radius([1e6, -1e6, 0.0], Float::INFINITY).len(),
+        index.len()
+    );
+
+    let mut poisson = Poisson2D::new();
+    poisson.with_periodic(true).with_seed(4);
+    let index = poisson.index();
+    assert_eq!(
+        index.within_radius([0.5, 0.5], Float::INFINITY).len(),
+        index.len()
+    );
+}
+
+#[test]
+fn into_index_keeps_returned_points() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(21);
+
+    let mut iter = poisson.iter();
+    let first: Vec<_> = iter.by_ref().take(10).collect();
+    let index = iter.into_index();
+
+    assert_eq!(index.len(), poisson.generate().len());
+    for point in first {
+        assert_eq!(index.nearest(point), Some(point));
+    }
+}
@@ -5,9 +5,15 @@
 // https://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use super::{Float, Poisson};
+use super::{Float, Poisson, SeededIter, SpatialIndex};
+use crate::elimination::{self, Space};
+use crate::grid::{distance_squared, wrap};
+use crate::space_filling;
 use rand::prelude::*;
 use rand_distr::StandardNormal;
+#[cfg(feature = "derive_serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::iter::FusedIterator;
 
 #[cfg(test)]
@@ -17,12 +23,229 @@ mod tests;
 pub type Point<const N: usize> = [Float; N];
 
 /// A Cell is the grid coordinates containing a given point
-type Cell<const N: usize> = [isize; N];
+///
+/// See [`Poisson::cell`] for the grid the coordinates refer to.
+pub type Cell<const N: usize> = [isize; N];
 
 #[cfg(not(feature = "small_rng"))]
-type Rand = rand_xoshiro::Xoshiro256StarStar;
+pub(crate) type Rand = rand_xoshiro::Xoshiro256StarStar;
 #[cfg(feature = "small_rng")]
-type Rand = rand_xoshiro::Xoshiro128StarStar;
+pub(crate) type Rand = rand_xoshiro::Xoshiro128StarStar;
+
+/// A custom region to fill, in place of the distribution's own box or shape
+pub(crate) type Region<const N: usize> = Box<dyn Fn(Point<N>) -> bool + Send + Sync>;
+
+/// How new candidate points are placed around an existing point
+///
+/// Every candidate lies in the annulus between `radius` and `2 * radius` from the point it was
+/// generated around; the strategies differ in where within that annulus they fall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "derive_serde", derive(Serialize, Deserialize))]
+pub enum Candidates {
+    /// The distance is uniformly random, in a uniformly random direction
+    ///
+    /// This is the original behavior. Because the outer part of the annulus is larger, candidates
+    /// are more crowded toward the inner edge.
+    UniformRadius,
+    /// Candidates are spread uniformly over the whole area (or volume) of the annulus
+    UniformArea,
+    /// Candidates are placed just beyond `radius`, at evenly spaced angles from a random start
+    ///
+    /// This is Martin Roberts' "ε-boundary" improvement, which packs points more densely and
+    /// wastes fewer attempts than sampling the whole annulus. Angles are only evenly spaced in
+    /// 2 dimensions; in any other number the directions are random.
+    Boundary,
+}
+
+// `#[default]` on enum variants needs a newer compiler than we support
+#[allow(clippy::derivable_impls)]
+impl Default for Candidates {
+    fn default() -> Self {
+        Candidates::UniformRadius
+    }
+}
+
+/// Which active point to grow the distribution from next
+///
+/// The policy doesn't change the kind of distribution produced, only the order in which its points
+/// are found; this shows when the points are drawn as they're generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "derive_serde", derive(Serialize, Deserialize))]
+pub enum ActivePolicy {
+    /// Pick a random active point each time
+    ///
+    /// This is the original behavior; the distribution grows in a scattered, chaotic way.
+    Random,
+    /// Take turns between the active points, in the order they were found
+    ///
+    /// After each new point the current one goes to the back of the queue, so the distribution
+    /// grows breadth-first, as an even frontier spreading out from the first point.
+    Fifo,
+    /// Always grow from the newest active point
+    ///
+    /// The distribution grows depth-first, snaking out along a single path.
+    Lifo,
+    /// Grow from the oldest active point until no more points fit around it
+    OldestFirst,
+}
+
+// `#[default]` on enum variants needs a newer compiler than we support
+#[allow(clippy::derivable_impls)]
+impl Default for ActivePolicy {
+    fn default() -> Self {
+        ActivePolicy::Random
+    }
+}
+
+/// The order in which the points of a distribution are returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "derive_serde", derive(Serialize, Deserialize))]
+pub enum Order {
+    /// Points are returned as soon as they're generated
+    ///
+    /// Each new point is near one found before it, so the first points are bunched together
+    /// around wherever the distribution started.
+    Generation,
+    /// Points are reordered so that the first `k` points are spread evenly over the whole space,
+    /// for every `k`
+    ///
+    /// This makes `.take(k)` a coarser version of the same distribution, e.g. for levels of
+    /// detail. The whole distribution has to be generated before the first point is returned.
+    Progressive,
+    /// Points are sorted along a Z-order (Morton) curve through the cells of the grid
+    ///
+    /// Points that are close in the order are close in space, which suits GPU buffers and
+    /// spatial databases. The whole distribution has to be generated before the first point is
+    /// returned.
+    Morton,
+    /// Points are sorted along a Hilbert curve through the cells of the grid
+    ///
+    /// Like [`Order::Morton`], but the curve never jumps between distant cells, so locality is
+    /// better still.
+    Hilbert,
+}
+
+// `#[default]` on enum variants needs a newer compiler than we support
+#[allow(clippy::derivable_impls)]
+impl Default for Order {
+    fn default() -> Self {
+        Order::Generation
+    }
+}
+
+/// How many times gaps may be split in half when filling them in a maximal distribution
+const MAX_GAP_DEPTH: usize = 12;
+/// How many times gaps must be split before those on the edge of a shape can be left unfilled
+const MIN_BOUNDARY_DEPTH: usize = 4;
+
+/// An axis-aligned box of space that may still have room for another point
+#[derive(Debug, Clone, Copy)]
+struct Gap<const N: usize> {
+    /// The lowest corner of the box
+    min: Point<N>,
+    /// The highest corner of the box
+    max: Point<N>,
+    /// Where the points that might cover the box are kept among the iterator's `gap_points`
+    near: (usize, usize),
+}
+
+impl<const N: usize> Gap<N> {
+    /// The center of the box
+    fn center(&self) -> Point<N> {
+        let mut center = self.min;
+        for (c, max) in center.iter_mut().zip(self.max.iter()) {
+            *c = (*c + max) / 2.0;
+        }
+
+        center
+    }
+
+    /// Each of the 2^N corners of the box
+    fn corners(&self) -> impl Iterator<Item = Point<N>> + '_ {
+        (0..1_usize << N).map(move |mask| {
+            let mut corner = self.min;
+            for (i, c) in corner.iter_mut().enumerate() {
+                if mask & (1 << i) != 0 {
+                    *c = self.max[i];
+                }
+            }
+
+            corner
+        })
+    }
+
+    /// Split the box in half along every dimension
+    fn split(&self) -> impl Iterator<Item = Gap<N>> + '_ {
+        let center = self.center();
+
+        self.corners().map(move |corner| {
+            let mut gap = Gap {
+                min: center,
+                max: center,
+                near: self.near,
+            };
+            for i in 0..N {
+                if corner[i] < center[i] {
+                    gap.min[i] = corner[i];
+                } else {
+                    gap.max[i] = corner[i];
+                }
+            }
+
+            gap
+        })
+    }
+}
+
+/// A vector pointing in a random direction, with every direction equally likely
+pub(crate) fn random_vector<const N: usize>(rng: &mut Rand) -> [Float; N] {
+    let mut vector: [Float; N] = [0.0; N];
+    for i in vector.iter_mut() {
+        *i = rng.sample(StandardNormal);
+    }
+
+    vector
+}
+
+/// A uniformly random point in the box with the given lowest corner and size
+pub(crate) fn random_point_in<const N: usize>(
+    rng: &mut Rand,
+    (origin, size): (Point<N>, [Float; N]),
+) -> Point<N> {
+    let mut point = origin;
+    for (p, dim) in point.iter_mut().zip(size.iter()) {
+        *p += rng.gen::<Float>() * dim;
+    }
+
+    point
+}
+
+/// Translate a point by `dist` in the direction of `vector`, which needn't be a unit vector
+pub(crate) fn step<const N: usize>(around: Point<N>, vector: [Float; N], dist: Float) -> Point<N> {
+    // Now find this vector's magnitude
+    let mag = vector.iter().map(|&x| x.powi(2)).sum::<Float>().sqrt();
+
+    // Dividing each of the vector's components by `mag` will produce a unit vector; then by
+    // multiplying each component by `dist`, we'll have a vector pointing `dist` away from the
+    // origin. If we then add each of those components to our point, we'll have effectively
+    // translated our point by `dist` in the chosen direction.
+    // Conveniently, we can do all of this in just one step!
+    let mut point = [0.0; N];
+    let translate = dist / mag; // compute this just once!
+    for i in 0..N {
+        point[i] = around[i] + vector[i] * translate;
+    }
+
+    point
+}
+
+/// How many grid cells of the given size it takes to cover `extent`
+///
+/// The grid is sized and indexed by this same count, so that the last, partial cell along each
+/// dimension gets its own slot rather than spilling over into the next row.
+fn cell_count(extent: Float, cell_size: Float) -> usize {
+    (extent / cell_size).ceil() as usize
+}
 
 /// An iterator over the points in the Poisson disk distribution
 pub struct Iter<const N: usize> {
@@ -32,59 +255,169 @@ pub struct Iter<const N: usize> {
     rng: Rand,
     /// The size of each cell in the grid
     cell_size: Float,
+    /// The lowest corner of the box the grid covers
+    origin: Point<N>,
+    /// The size of the box the grid covers
+    extent: [Float; N],
+    /// The custom region to fill, if any
+    region: Option<Region<N>>,
+    /// The size of the box if it wraps around
+    period: Option<[Float; N]>,
     /// The grid stores spatially-oriented samples for fast checking of neighboring sample points
     grid: Vec<Option<Point<N>>>,
     /// A list of valid points that we have not yet visited
-    active: Vec<Point<N>>,
+    active: VecDeque<Point<N>>,
+    /// The fraction of a turn at which to place the next [`Candidates::Boundary`] candidate
+    turn: Float,
+    /// The point the distribution started from, until it has been returned
+    first: Option<Point<N>>,
+    /// The whole distribution, reordered, once it has been generated
+    ordered: Option<std::vec::IntoIter<Point<N>>>,
+    /// Whether we've looked for the gaps Bridson's algorithm left behind
+    searched_gaps: bool,
+    /// The gaps that may still have room for another point
+    gaps: Vec<Gap<N>>,
+    /// The points near each gap, so that filling and refining them needn't search the grid
+    gap_points: Vec<Point<N>>,
+    /// The points placed since the gaps' nearby points were gathered
+    gap_added: Vec<Point<N>>,
+    /// How many times the gaps have been split
+    gap_depth: usize,
+    /// How many more darts to throw before splitting the gaps again
+    darts: usize,
 }
 
 impl<const N: usize> Iter<N> {
     /// Create an iterator over the specified distribution
     pub(crate) fn new(distribution: Poisson<N>) -> Self {
+        // The grid covers the bounding box of the space we're filling
+        let bounds = distribution.bounds();
+
+        Self::build(distribution, bounds, None, &[])
+    }
+
+    /// Create an iterator that carries on from points that are already in place
+    ///
+    /// The points should be within the distribution's space and at least its radius apart; any
+    /// that aren't are left out. They are not returned by the iterator, but new points grow out
+    /// from them.
+    pub(crate) fn from_points(distribution: Poisson<N>, points: &[Point<N>]) -> Self {
+        let bounds = distribution.bounds();
+
+        Self::build(distribution, bounds, None, points)
+    }
+
+    /// Create an iterator that fills a custom region lying within `bounds`
+    ///
+    /// The bounds are the lowest corner and the size of the box holding the region, in the same
+    /// form as [`Poisson::bounds`].
+    pub(crate) fn in_region(
+        distribution: Poisson<N>,
+        bounds: (Point<N>, [Float; N]),
+        region: Region<N>,
+    ) -> Self {
+        Self::build(distribution, bounds, Some(region), &[])
+    }
+
+    fn build(
+        distribution: Poisson<N>,
+        (origin, extent): (Point<N>, [Float; N]),
+        region: Option<Region<N>>,
+        points: &[Point<N>],
+    ) -> Self {
         // We maintain a grid of our samples for faster radius checking
         let cell_size = distribution.radius / (N as Float).sqrt();
 
         // If we were not given a seed, generate one non-deterministically
-        let mut rng = match distribution.seed {
-            None => Rand::from_entropy(),
-            Some(seed) => Rand::seed_from_u64(seed),
-        };
+        let rng = distribution.rng();
 
         // Calculate the amount of storage we'll need for our n-dimensional grid, which is stored
         // as a single-dimensional array.
-        let grid_size: usize = distribution
-            .dimensions
-            .iter()
-            .map(|n| (n / cell_size).ceil() as usize)
-            .product();
+        let grid_size: usize = extent.iter().map(|&n| cell_count(n, cell_size)).product();
 
-        // We have to generate an initial point, just to ensure we've got *something* in the active list
-        let mut first_point = [0.0; N];
-        for (i, dim) in first_point.iter_mut().zip(distribution.dimensions.iter()) {
-            *i = rng.gen::<Float>() * dim;
-        }
+        let period = match region {
+            None => distribution.period(),
+            Some(_) => None,
+        };
 
         let mut iter = Iter {
             distribution,
             rng,
             cell_size,
+            origin,
+            extent,
+            region,
+            period,
             grid: vec![None; grid_size],
-            active: Vec::new(),
+            active: VecDeque::new(),
+            turn: 0.0,
+            first: None,
+            ordered: None,
+            searched_gaps: false,
+            gaps: Vec::new(),
+            gap_points: Vec::new(),
+            gap_added: Vec::new(),
+            gap_depth: 0,
+            darts: 0,
         };
-        // Don't forget to add our initial point
-        iter.add_point(first_point);
+
+        for &point in points {
+            // Leave out any that don't fit, just as we would our own candidates
+            if iter.in_space(point) && !iter.in_neighborhood(point) {
+                iter.add_point(point);
+            }
+        }
+
+        // We have to generate an initial point, just to ensure we've got *something* in the active
+        // list, unless we've been given some to start from
+        if iter.active.is_empty() {
+            if let Some(first_point) = iter.generate_first_point() {
+                iter.add_point(first_point);
+                iter.first = Some(first_point);
+            }
+        }
 
         iter
     }
 
+    /// Generate a random point within our space to start the distribution from
+    fn generate_first_point(&mut self) -> Option<Point<N>> {
+        // Shapes may fill only a small part of their bounding box, so we may have to try a few times
+        let attempts = if self.distribution.shape.is_some() || self.region.is_some() {
+            1000
+        } else {
+            1
+        };
+
+        for _ in 0..attempts {
+            let point = random_point_in(&mut self.rng, (self.origin, self.extent));
+
+            if self.in_space(point) {
+                return Some(point);
+            }
+        }
+
+        // Long and thin shapes can evade random guesses; fall back to a point we know is inside
+        self.distribution
+            .shape
+            .as_ref()
+            .map(|shape| shape.center())
+            .filter(|&point| self.in_space(point))
+    }
+
     /// Add a point to our pattern
     fn add_point(&mut self, point: Point<N>) {
         // Add it to the active list
-        self.active.push(point);
+        self.active.push_back(point);
 
         // Now stash this point in our grid
         let idx = self.point_to_idx(point);
         self.grid[idx] = Some(point);
+
+        if self.searched_gaps {
+            // The gaps only know about the points that were near them when they were found
+            self.gap_added.push(point);
+        }
     }
 
     /// Convert a point into grid cell coordinates
@@ -92,7 +425,7 @@ impl<const N: usize> Iter<N> {
         let mut cell = [0_isize; N];
 
         for i in 0..N {
-            cell[i] = (point[i] / self.cell_size) as isize;
+            cell[i] = ((point[i] - self.origin[i]) / self.cell_size) as isize;
         }
 
         cell
@@ -101,9 +434,9 @@ impl<const N: usize> Iter<N> {
     /// Convert a cell into a grid vector index
     fn cell_to_idx(&self, cell: Cell<N>) -> usize {
         cell.iter()
-            .zip(self.distribution.dimensions.iter())
+            .zip(self.extent.iter())
             .fold(0, |acc, (pn, dn)| {
-                acc * (dn / self.cell_size) as usize + *pn as usize
+                acc * cell_count(*dn, self.cell_size) + *pn as usize
             })
     }
 
@@ -114,39 +447,49 @@ impl<const N: usize> Iter<N> {
 
     /// Generate a random point between `radius` and `2 * radius` away from the given point
     fn generate_random_point(&mut self, around: Point<N>) -> Point<N> {
+        let radius = self.distribution.radius;
+
         // Pick a random distance away from our point
-        let dist = self.distribution.radius * (1.0 + self.rng.gen::<Float>());
+        let dist = match self.distribution.candidates {
+            Candidates::UniformRadius => radius * (1.0 + self.rng.gen::<Float>()),
+            // The volume within distance `d` grows with `d^N`, so we invert that
+            Candidates::UniformArea => {
+                let shells = (2.0 as Float).powi(N as i32) - 1.0;
+                radius * (1.0 + self.rng.gen::<Float>() * shells).powf(1.0 / N as Float)
+            }
+            // Far enough beyond `radius` to survive rounding, but no further
+            Candidates::Boundary => radius * (1.0 + Float::EPSILON.sqrt()),
+        };
 
         // Generate a randomly distributed vector
-        let mut vector: [Float; N] = [0.0; N];
-        for i in vector.iter_mut() {
-            *i = self.rng.sample(StandardNormal);
-        }
-        // Now find this new vector's magnitude
-        let mag = vector.iter().map(|&x| x.powi(2)).sum::<Float>().sqrt();
-
-        // Dividing each of the vector's components by `mag` will produce a unit vector; then by
-        // multiplying each component by `dist`, we'll have a vector pointing `dist` away from the
-        // origin. If we then add each of those components to our point, we'll have effectively
-        // translated our point by `dist` in a randomly chosen direction.
-        // Conveniently, we can do all of this in just one step!
-        let mut point = [0.0; N];
-        let translate = dist / mag; // compute this just once!
-        for i in 0..N {
-            point[i] = around[i] + vector[i] * translate;
-        }
+        let vector = if N == 2 && self.distribution.candidates == Candidates::Boundary {
+            // Step evenly around the circle, so the candidates don't bunch up
+            let angle = self.turn * 2.0 * std::f64::consts::PI as Float;
+            self.turn += 1.0 / self.distribution.num_samples as Float;
+            let mut vector = [0.0; N];
+            vector[0] = angle.cos();
+            vector[1] = angle.sin();
+            vector
+        } else {
+            random_vector(&mut self.rng)
+        };
 
-        point
+        step(around, vector, dist)
     }
 
     /// Returns true if the point is within the bounds of our space.
     ///
-    /// This is true if 0 ≤ point[i] < dimensions[i]
+    /// For a box this is true if 0 ≤ point[i] < dimensions[i]; for a [`Shape`](crate::Shape) or a
+    /// custom region the point must also lie inside the shape or region itself.
     fn in_space(&self, point: Point<N>) -> bool {
-        point
-            .iter()
-            .zip(self.distribution.dimensions.iter())
-            .all(|(p, d)| *p >= 0. && p < d)
+        match &self.region {
+            None => self.distribution.contains(point),
+            Some(region) => {
+                (0..N).all(|i| {
+                    point[i] >= self.origin[i] && point[i] < self.origin[i] + self.extent[i]
+                }) && region(point)
+            }
+        }
     }
 
     /// Returns true if the cell is within the bounds of our grid.
@@ -154,81 +497,613 @@ impl<const N: usize> Iter<N> {
     /// This is true if 0 ≤ `cell[i]` ≤ `ceiling(space[i] / cell_size)`
     fn in_grid(&self, cell: Cell<N>) -> bool {
         cell.iter()
-            .zip(self.distribution.dimensions.iter())
-            .all(|(c, d)| *c >= 0 && *c < (*d / self.cell_size).ceil() as isize)
+            .zip(self.extent.iter())
+            .all(|(c, d)| *c >= 0 && *c < cell_count(*d, self.cell_size) as isize)
     }
 
     /// Returns true if there is at least one other sample point within `radius` of this point
     fn in_neighborhood(&self, point: Point<N>) -> bool {
-        let cell = self.point_to_cell(point);
+        if self.period.is_none() && !self.in_grid(self.point_to_cell(point)) {
+            // Points outside the grid are out of bounds, and by definition have no neighbors
+            return false;
+        }
 
         // We'll compare to distance squared, so we can skip the square root operation for better performance
         let r_squared = self.distribution.radius.powi(2);
 
-        for mut carry in 0.. {
-            let mut neighbor = cell;
+        self.any_near(point, self.distribution.radius, |point2| {
+            distance_squared(point, point2, self.period) < r_squared
+        })
+    }
+
+    /// How many cells away from a point another point within `radius` of it may lie
+    ///
+    /// For the distribution's own radius this is `ceil(√N)` cells, since cells are `radius / √N`
+    /// wide. That's no more than 2 for up to 4 dimensions, but grows beyond it from 5 up.
+    fn reach(&self, radius: Float) -> isize {
+        (radius / self.cell_size).ceil() as isize
+    }
+
+    /// Returns true if `f` is true for any sample point that might be within `radius` of this point
+    ///
+    /// Points further away may be tested too, so `f` needs to check the distance itself.
+    fn any_near<F>(&self, point: Point<N>, radius: Float, mut f: F) -> bool
+    where
+        F: FnMut(Point<N>) -> bool,
+    {
+        self.any_cell_near(point, radius, |idx| match self.grid[idx] {
+            Some(point2) => f(point2),
+            None => false,
+        })
+    }
+
+    /// Returns true if `f` is true for the index of any grid cell that might be within `radius` of
+    /// this point
+    ///
+    /// The cells visited from a point in one cell are just those from which a point in the other
+    /// would visit it.
+    fn any_cell_near<F>(&self, point: Point<N>, radius: Float, mut f: F) -> bool
+    where
+        F: FnMut(usize) -> bool,
+    {
+        let center = self.wrap_cell(self.point_to_cell(point));
+
+        // The range of cells to visit along each dimension, which never needs to be wider than
+        // the grid itself however large the radius
+        let mut lo = [0_isize; N];
+        let mut hi = [0_isize; N];
+        for i in 0..N {
+            let count = cell_count(self.extent[i], self.cell_size) as isize;
+            let reach = self.reach(radius).min(count);
 
-            // We can add our current iteration count to visit each neighbor cell
-            for i in (&mut neighbor).iter_mut() {
-                // We clamp our addition to the range [-2, 2] for each cell
-                *i += carry % 5 - 2;
-                // Since we modulo by 5 to get the right range, integer division by 5 "advances" us
-                carry /= 5;
+            if self.period.is_some() {
+                // Across the seam of a periodic box the last, partial cell doesn't count as a
+                // whole one
+                let reach = reach + 1;
+                if 2 * reach + 1 >= count {
+                    // The window wraps all the way around, so visit each cell once
+                    lo[i] = 0;
+                    hi[i] = count - 1;
+                } else {
+                    lo[i] = center[i] - reach;
+                    hi[i] = center[i] + reach;
+                }
+            } else {
+                // A point outside the grid is no closer to any cell than the nearest edge of it is
+                let center = center[i].max(0).min(count - 1);
+                lo[i] = (center - reach).max(0);
+                hi[i] = (center + reach).min(count - 1);
             }
 
-            if carry > 0 {
-                // If we've "overflowed" then we've already tested every neighbor cell
+            if lo[i] > hi[i] {
+                // None of the grid is close enough
                 return false;
             }
-            if !self.in_grid(neighbor) {
-                // Skip anything beyond the bounds of our grid
+        }
+
+        let mut cell = lo;
+        loop {
+            let neighbor = self.wrap_cell(cell);
+            if f(self.cell_to_idx(neighbor)) {
+                return true;
+            }
+
+            // Advance to the next cell, odometer-style
+            let mut i = 0;
+            loop {
+                if i == N {
+                    return false;
+                }
+                if cell[i] < hi[i] {
+                    cell[i] += 1;
+                    break;
+                }
+                cell[i] = lo[i];
+                i += 1;
+            }
+        }
+    }
+
+    /// Returns true if one of the given points lies within `radius` of the whole gap, so that no
+    /// new point could be placed anywhere in it
+    fn is_covered(&self, gap: &Gap<N>, points: &[Point<N>]) -> bool {
+        let r_squared = self.distribution.radius.powi(2);
+
+        // Where a shape or region cuts through the gap, the corners outside it don't need covering;
+        // but they can only be ignored once the gap is small, or we'd miss the space beside them
+        let bounded = (self.region.is_some() || self.distribution.shape.is_some())
+            && self.gap_depth >= MIN_BOUNDARY_DEPTH;
+        if !bounded {
+            // Distances add up separately along each axis, so the farthest corner from a point
+            // is whichever end of the gap is farther along each one
+            return points.iter().any(|&point| {
+                let farthest: Float = (0..N)
+                    .map(|i| {
+                        let far = |end: Float| {
+                            let diff = (end - point[i]).abs();
+                            match self.period {
+                                Some(period) => diff.min(period[i] - diff),
+                                None => diff,
+                            }
+                        };
+                        far(gap.min[i]).max(far(gap.max[i])).powi(2)
+                    })
+                    .sum();
+
+                farthest < r_squared
+            });
+        }
+
+        let corners: Vec<Point<N>> = gap
+            .corners()
+            .filter(|&corner| self.in_space(corner))
+            .collect();
+
+        // A disk is convex, so it covers the whole gap if it covers all of its corners
+        points.iter().any(|&point| {
+            corners
+                .iter()
+                .all(|&corner| distance_squared(corner, point, self.period) < r_squared)
+        })
+    }
+
+    /// Returns true if any part of the gap might lie within our space
+    ///
+    /// Gaps are only tested at their corners and center, so slivers of a shape narrower than the
+    /// gap itself may be missed.
+    fn overlaps_space(&self, gap: &Gap<N>) -> bool {
+        self.in_space(gap.center()) || gap.corners().any(|corner| self.in_space(corner))
+    }
+
+    /// Collect the grid cells left empty by Bridson's algorithm that may still have room
+    fn find_gaps(&mut self) {
+        // Pair each empty cell with the points that might cover part of it. There are far fewer
+        // points than empty cells, so it's quicker to visit the cells around each point than the
+        // points around each cell.
+        let reach = self.distribution.radius + self.cell_size * (N as Float).sqrt() / 2.0;
+        let mut pairs = Vec::new();
+        for point in self.grid.iter().flatten() {
+            self.any_cell_near(*point, reach, |idx| {
+                if self.grid[idx].is_none()
+                    && distance_squared(self.cell_gap(idx).center(), *point, self.period)
+                        <= reach.powi(2)
+                {
+                    pairs.push((idx, *point));
+                }
+                false
+            });
+        }
+        pairs.sort_by_key(|&(idx, _)| idx);
+
+        let mut gaps = Vec::new();
+        let mut pairs = pairs.into_iter().peekable();
+        for idx in 0..self.grid.len() {
+            if self.grid[idx].is_some() {
+                continue;
+            }
+
+            let mut gap = self.cell_gap(idx);
+            let near: Vec<Point<N>> = std::iter::from_fn(|| pairs.next_if(|&(i, _)| i == idx))
+                .map(|(_, point)| point)
+                .collect();
+            if !self.overlaps_space(&gap) || self.is_covered(&gap, &near) {
                 continue;
             }
 
-            if let Some(point2) = self.grid[self.cell_to_idx(neighbor)] {
-                let neighbor_dist_squared = point
-                    .iter()
-                    .zip(point2.iter())
-                    .map(|(a, b)| (a - b).powi(2))
-                    .sum::<Float>();
+            gap.near = (self.gap_points.len(), self.gap_points.len() + near.len());
+            self.gap_points.extend(near);
+            gaps.push(gap);
+        }
+
+        self.gaps = gaps;
+        self.gap_added.clear();
+        self.darts = self.gaps.len();
+    }
+
+    /// The box covered by the grid cell with the given index, clipped to our own box
+    fn cell_gap(&self, idx: usize) -> Gap<N> {
+        let mut min = self.origin;
+        let mut max = self.origin;
+        let mut rest = idx;
+        for i in (0..N).rev() {
+            let count = cell_count(self.extent[i], self.cell_size);
+            min[i] += (rest % count) as Float * self.cell_size;
+            max[i] = (min[i] + self.cell_size).min(self.origin[i] + self.extent[i]);
+            rest /= count;
+        }
+
+        Gap {
+            min,
+            max,
+            near: (0, 0),
+        }
+    }
+
+    /// Throw darts into the remaining gaps, refining them until none are left
+    ///
+    /// This is the flat hyperoctree approach of Ebeida et al., "Efficient Maximal Poisson-Disk
+    /// Sampling" (2011): gaps that are completely covered are discarded, and the rest are split
+    /// in half along every dimension, until the gaps are too small to matter.
+    fn fill_gaps(&mut self) -> Option<Point<N>> {
+        while !self.gaps.is_empty() {
+            if self.darts == 0 {
+                // We've thrown enough darts at this level; refine the gaps and try again
+                self.gap_depth += 1;
+                if self.gap_depth > MAX_GAP_DEPTH {
+                    self.gaps.clear();
+                    break;
+                }
+
+                let mut gaps = Vec::new();
+                let mut gap_points = Vec::new();
+                for gap in std::mem::take(&mut self.gaps) {
+                    // Any point that could cover part of the gap is near the whole gap, and was
+                    // either near the larger gap it came from or has been placed since
+                    let near = self.near_gap(&gap);
+                    if self.is_covered(&gap, &near) {
+                        continue;
+                    }
 
-                if neighbor_dist_squared < r_squared {
-                    return true;
+                    let range = (gap_points.len(), gap_points.len() + near.len());
+                    gaps.extend(
+                        gap.split()
+                            .map(|gap| Gap { near: range, ..gap })
+                            .filter(|gap| self.overlaps_space(gap) && !self.is_covered(gap, &near)),
+                    );
+                    gap_points.extend(near);
                 }
+                self.gaps = gaps;
+                self.gap_points = gap_points;
+                self.gap_added.clear();
+                self.darts = self.gaps.len();
+                continue;
+            }
+
+            self.darts -= 1;
+            let i = self.rng.gen_range(0..self.gaps.len());
+            let gap = self.gaps[i];
+
+            let mut point = gap.min;
+            for (p, max) in point.iter_mut().zip(gap.max.iter()) {
+                *p += self.rng.gen::<Float>() * (max - *p);
+            }
+
+            if self.in_space(point) && !self.crowds_gap(point, &gap) {
+                self.add_point(point);
+
+                return Some(point);
             }
         }
 
-        // Rust can't tell the previous loop will always reach one of the `return` statements...
-        false
+        None
     }
-}
 
-impl<const N: usize> Iterator for Iter<N> {
-    type Item = Point<N>;
+    /// The points that might lie within `radius` of any part of the gap, out of those near the
+    /// larger gap it came from and those placed since
+    fn near_gap(&self, gap: &Gap<N>) -> Vec<Point<N>> {
+        let center = gap.center();
+        let half_diagonal = distance_squared(center, gap.max, None).sqrt();
+        let reach = (self.distribution.radius + half_diagonal).powi(2);
+
+        let (start, end) = gap.near;
+        self.gap_points[start..end]
+            .iter()
+            .chain(self.gap_added.iter())
+            .filter(|&&point| distance_squared(center, point, self.period) <= reach)
+            .copied()
+            .collect()
+    }
+
+    /// Returns true if a point in the gap is within `radius` of another point, like
+    /// [`Iter::in_neighborhood`] but without searching the grid
+    fn crowds_gap(&self, point: Point<N>, gap: &Gap<N>) -> bool {
+        let r_squared = self.distribution.radius.powi(2);
+
+        let (start, end) = gap.near;
+        self.gap_points[start..end]
+            .iter()
+            .chain(self.gap_added.iter())
+            .any(|&other| distance_squared(point, other, self.period) < r_squared)
+    }
+
+    /// Wrap a cell around to the other side of a periodic grid; other grids are left alone
+    fn wrap_cell(&self, mut cell: Cell<N>) -> Cell<N> {
+        if self.period.is_some() {
+            for (c, d) in cell.iter_mut().zip(self.extent.iter()) {
+                *c = c.rem_euclid(cell_count(*d, self.cell_size) as isize);
+            }
+        }
+
+        cell
+    }
+
+    /// Every point within `radius` of the given point, in no particular order
+    pub(crate) fn within(&self, point: Point<N>, radius: Float) -> Vec<Point<N>> {
+        let r_squared = radius.powi(2);
+
+        let mut points = Vec::new();
+        self.any_near(point, radius, |point2| {
+            if distance_squared(point, point2, self.period) <= r_squared {
+                points.push(point2);
+            }
+            false
+        });
+
+        points
+    }
+
+    /// The furthest any point in our grid could be from the given point
+    pub(crate) fn max_distance(&self, point: Point<N>) -> Float {
+        let furthest = match self.period {
+            // Nothing is more than half the box away in any dimension
+            Some(period) => period.iter().map(|d| (d / 2.0).powi(2)).sum::<Float>(),
+            None => (0..N)
+                .map(|i| {
+                    let lo = (point[i] - self.origin[i]).abs();
+                    let hi = (point[i] - self.origin[i] - self.extent[i]).abs();
+                    lo.max(hi).powi(2)
+                })
+                .sum::<Float>(),
+        };
+
+        furthest.sqrt()
+    }
+
+    /// Squared distance between two points, the shortest way around a periodic box
+    pub(crate) fn distance_squared(&self, a: Point<N>, b: Point<N>) -> Float {
+        distance_squared(a, b, self.period)
+    }
+
+    /// Every point in the distribution so far
+    pub(crate) fn points(&self) -> impl Iterator<Item = Point<N>> + '_ {
+        self.grid.iter().filter_map(|cell| *cell)
+    }
+
+    /// Add a point if it's within our space and far enough from the others, without growing the
+    /// distribution around it
+    pub(crate) fn insert(&mut self, point: Point<N>) -> bool {
+        if !self.in_space(point) || self.in_neighborhood(point) {
+            return false;
+        }
+
+        let idx = self.point_to_idx(point);
+        self.grid[idx] = Some(point);
+
+        true
+    }
+
+    /// Remove every point for which `f` is true, returning them
+    pub(crate) fn remove_where<F>(&mut self, mut f: F) -> Vec<Point<N>>
+    where
+        F: FnMut(Point<N>) -> bool,
+    {
+        let mut removed = Vec::new();
+        for cell in self.grid.iter_mut() {
+            if let Some(point) = *cell {
+                if f(point) {
+                    *cell = None;
+                    removed.push(point);
+                }
+            }
+        }
+        // Every active point has a cell of its own, which is only empty if we just removed it
+        let active = std::mem::take(&mut self.active);
+        self.active = active
+            .into_iter()
+            .filter(|&point| self.grid[self.point_to_idx(point)] == Some(point))
+            .collect();
+
+        removed
+    }
+
+    /// Generate new points within a region, around the points already in place
+    ///
+    /// The region must lie within our space; `bounds` holds it, in the same form as
+    /// [`Poisson::bounds`]. Returns the new points.
+    pub(crate) fn refill(
+        &mut self,
+        region: Region<N>,
+        (min, size): (Point<N>, [Float; N]),
+    ) -> Vec<Point<N>> {
+        let previous = self.region.replace(region);
+        let mut added = Vec::new();
+
+        // Grow from the points near the region, as they may be able to reach into it
+        let reach = 2.0 * self.distribution.radius;
+        let near = |point: &Point<N>| {
+            (0..N).all(|i| point[i] >= min[i] - reach && point[i] < min[i] + size[i] + reach)
+        };
+        self.active = if self.period.is_some() {
+            // Points near the region could be on the far side of the box, so grow from them all
+            self.points().collect()
+        } else {
+            self.points().filter(near).collect()
+        };
+
+        // And in case none of them can, start from somewhere inside the region
+        for _ in 0..1000 {
+            let point = wrap(random_point_in(&mut self.rng, (min, size)), self.period);
+
+            if self.in_space(point) && !self.in_neighborhood(point) {
+                self.add_point(point);
+                added.push(point);
+                break;
+            }
+        }
+
+        // Search for the region's gaps afresh, in a maximal distribution
+        self.searched_gaps = false;
+        self.gaps.clear();
+        self.gap_points.clear();
+        self.gap_added.clear();
+        self.gap_depth = 0;
+        self.darts = 0;
+
+        while let Some(point) = self.generate_next() {
+            added.push(point);
+        }
+
+        self.region = previous;
+
+        added
+    }
+
+    /// Put the whole distribution in the requested order
+    fn reorder(&self, points: Vec<Point<N>>) -> Vec<Point<N>> {
+        match self.distribution.order {
+            Order::Generation => points,
+            Order::Progressive => {
+                // We don't know the volume of a custom region, but its bounding box will do
+                let volume = match self.region {
+                    None => self.distribution.volume(),
+                    Some(_) => self.extent.iter().product(),
+                };
+                let space = Space {
+                    bounds: (self.origin, self.extent),
+                    volume,
+                    period: self.period,
+                };
+
+                elimination::progressive(&space, &points)
+                    .into_iter()
+                    .map(|idx| points[idx])
+                    .collect()
+            }
+            Order::Morton | Order::Hilbert => {
+                let cells: Vec<Cell<N>> = points.iter().map(|&p| self.point_to_cell(p)).collect();
+                let order = match self.distribution.order {
+                    Order::Morton => space_filling::morton_order(&cells),
+                    _ => space_filling::hilbert_order(&cells),
+                };
+
+                order.into_iter().map(|idx| points[idx]).collect()
+            }
+        }
+    }
+
+    /// Generate the next point of the distribution
+    fn generate_next(&mut self) -> Option<Point<N>> {
+        if let Some(point) = self.first.take() {
+            return Some(point);
+        }
 
-    fn next(&mut self) -> Option<Point<N>> {
         while !self.active.is_empty() {
-            let i = self.rng.gen_range(0..self.active.len());
+            let i = match self.distribution.active_policy {
+                ActivePolicy::Random => self.rng.gen_range(0..self.active.len()),
+                ActivePolicy::Fifo | ActivePolicy::OldestFirst => 0,
+                ActivePolicy::Lifo => self.active.len() - 1,
+            };
+            // Start evenly spaced candidates from a random angle
+            if self.distribution.candidates == Candidates::Boundary {
+                self.turn = self.rng.gen();
+            }
 
             for _ in 0..self.distribution.num_samples {
                 // Generate up to `num_samples` random points between radius and 2*radius from the current point
                 let point = self.generate_random_point(self.active[i]);
+                // In a periodic box, points that fall off one side come back in on the other
+                let point = wrap(point, self.period);
 
                 // Ensure we've picked a point inside the bounds of our rectangle, and more than `radius`
                 // distance from any other sampled point
                 if self.in_space(point) && !self.in_neighborhood(point) {
                     // We've got a good one!
                     self.add_point(point);
+                    if self.distribution.active_policy == ActivePolicy::Fifo {
+                        // Let the other active points have their turn before this one again
+                        self.active.rotate_left(1);
+                    }
 
                     return Some(point);
                 }
             }
 
-            self.active.swap_remove(i);
+            // Only a random pick can be swapped out of place without changing the order
+            match self.distribution.active_policy {
+                ActivePolicy::Random => self.active.swap_remove_back(i),
+                _ => self.active.remove(i),
+            };
+        }
+
+        if self.distribution.maximal {
+            if !self.searched_gaps {
+                self.searched_gaps = true;
+                self.find_gaps();
+            }
+            // Bridson's algorithm will carry on from any new point before returning to the gaps
+            if let Some(point) = self.fill_gaps() {
+                return Some(point);
+            }
         }
 
         None
     }
 }
 
+impl<const N: usize> Iter<N> {
+    /// Number each point in the order it's returned, and give it a seed of its own
+    ///
+    /// Each point's seed is derived from its number and the distribution's seed, not drawn from
+    /// the random number generator placing the points. Random attributes made from it, such as
+    /// a rotation or scale, are reproducible for a given seed, and changing how they're made
+    /// can't move the points themselves. Without a seed, the points' seeds are random too.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// # use rand::{Rng, SeedableRng};
+    /// # use rand::rngs::StdRng;
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_seed(0xF0_2E57);
+    ///
+    /// for tree in poisson.iter().seeded() {
+    ///     let mut rng = StdRng::seed_from_u64(tree.seed);
+    ///     let height: f64 = rng.gen_range(5.0..10.0);
+    ///     println!("tree {} at {:?} is {}m tall", tree.id, tree.point, height);
+    /// }
+    /// ```
+    pub fn seeded(self) -> SeededIter<N> {
+        let seed = match self.distribution.seed {
+            None => rand::random(),
+            Some(seed) => seed,
+        };
+
+        SeededIter::new(self, seed)
+    }
+
+    /// Finish generating the distribution, and keep its grid for finding the points near any
+    /// position
+    ///
+    /// Any points not yet returned by the iterator are generated, and are included in the index.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let index = Poisson2D::new().iter().into_index();
+    ///
+    /// let nearest = index.nearest([0.5, 0.5]);
+    /// ```
+    pub fn into_index(mut self) -> SpatialIndex<N> {
+        self.by_ref().for_each(drop);
+
+        let radius = self.distribution.radius;
+        SpatialIndex::new(self, radius)
+    }
+}
+
+impl<const N: usize> Iterator for Iter<N> {
+    type Item = Point<N>;
+
+    fn next(&mut self) -> Option<Point<N>> {
+        if self.distribution.order == Order::Generation {
+            return self.generate_next();
+        }
+
+        if self.ordered.is_none() {
+            let points: Vec<Point<N>> = std::iter::from_fn(|| self.generate_next()).collect();
+            self.ordered = Some(self.reorder(points).into_iter());
+        }
+
+        self.ordered.as_mut().and_then(Iterator::next)
+    }
+}
+
 impl<const N: usize> FusedIterator for Iter<N> {}
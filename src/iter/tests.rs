@@ -6,6 +6,7 @@
 // copied, modified, or distributed except according to those terms.
 
 use super::*;
+use crate::tests::{assert_spaced, largest_gap, min_distance};
 use crate::{Poisson2D, Poisson3D};
 
 #[test]
@@ -91,6 +92,31 @@ fn n_dimensional_cell_to_idx() {
     assert_eq!(iter.cell_to_idx([2, 1]), 7);
 }
 
+#[test]
+fn uneven_grid_cells_have_their_own_index() {
+    // Neither side is a whole number of cells, so the last cell along each one is partial
+    let mut poisson = Poisson::<3>::new();
+    poisson.with_dimensions([1.05, 0.55, 0.35], 0.1);
+    let iter = poisson.iter();
+
+    let counts: Vec<isize> = iter
+        .extent
+        .iter()
+        .map(|&d| cell_count(d, iter.cell_size) as isize)
+        .collect();
+    let mut seen = vec![false; iter.grid.len()];
+    for x in 0..counts[0] {
+        for y in 0..counts[1] {
+            for z in 0..counts[2] {
+                let idx = iter.cell_to_idx([x, y, z]);
+                assert!(!seen[idx], "cell {:?} shares index {}", [x, y, z], idx);
+                seen[idx] = true;
+            }
+        }
+    }
+    assert!(seen.iter().all(|&s| s));
+}
+
 #[test]
 fn n_dimensional_point_to_cell() {
     let poisson = Poisson::<3> {
@@ -146,6 +172,41 @@ fn adding_points() {
     assert_eq!(iter.grid[idx], Some(point));
 }
 
+#[test]
+fn starting_point_is_returned_first() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(0xF125);
+    let mut iter = poisson.iter();
+    let start = iter.active[0];
+
+    assert_eq!(iter.next(), Some(start));
+    // It's only returned once
+    assert!(iter.all(|point| point != start));
+}
+
+#[test]
+fn removed_points_leave_the_active_list() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(0xACE);
+    let mut iter = poisson.iter();
+    // Generate part of the distribution, so some points are still active
+    let points: Vec<_> = iter.by_ref().take(20).collect();
+    let active: Vec<_> = iter.active.iter().copied().collect();
+
+    // Split them down the middle
+    let mut xs: Vec<_> = points.iter().map(|p| p[0]).collect();
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let split = xs[10];
+
+    let removed = iter.remove_where(|[x, _]| x < split);
+
+    assert!(removed.iter().all(|&[x, _]| x < split));
+    assert!(points.iter().all(|p| removed.contains(p) == (p[0] < split)));
+    let expected: Vec<_> = active.into_iter().filter(|&[x, _]| x >= split).collect();
+    assert!(!expected.is_empty());
+    assert_eq!(iter.active.iter().copied().collect::<Vec<_>>(), expected);
+}
+
 #[test]
 fn point_generation_lies_within_radius() {
     let mut iter = Poisson2D::new().iter();
@@ -262,3 +323,345 @@ fn out_of_bounds_point_is_not_neighbor() {
 
     assert!(!iter.in_neighborhood([1.1, 1.1])); // Out of bounds by definition has no neighbors
 }
+
+#[test]
+fn high_dimensional_neighbors_are_found_three_cells_away() {
+    let mut poisson = Poisson::<5>::new();
+    poisson.with_dimensions([3.0; 5], 1.0);
+    let mut iter = poisson.iter();
+    // Flush the grid
+    iter.grid = vec![None; iter.grid.len()];
+
+    // Cells are 1/√5 wide, so these points are three cells apart but closer than the radius
+    iter.add_point([0.44, 0.1, 0.1, 0.1, 0.1]);
+    let near = [1.39, 0.1, 0.1, 0.1, 0.1];
+    assert_eq!(iter.point_to_cell(near)[0], 3);
+
+    assert_eq!(iter.reach(1.0), 3);
+    assert!(iter.in_neighborhood(near));
+}
+
+#[test]
+fn periodic_neighbors_are_found_across_the_seam() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([1.0, 0.5], 0.1).with_periodic(true);
+    let mut iter = poisson.iter();
+    // Flush the grid
+    iter.grid = vec![None; iter.grid.len()];
+
+    iter.add_point([0.98, 0.49]);
+
+    assert!(iter.in_neighborhood([0.02, 0.49])); // Across the left and right sides
+    assert!(iter.in_neighborhood([0.98, 0.01])); // Across the top and bottom
+    assert!(iter.in_neighborhood([0.02, 0.01])); // Across the corner
+    assert!(!iter.in_neighborhood([0.5, 0.25]));
+
+    // Without wrapping around, those are all too far away
+    iter.period = None;
+    assert!(!iter.in_neighborhood([0.02, 0.49]));
+    assert!(!iter.in_neighborhood([0.02, 0.01]));
+}
+
+#[test]
+fn periodic_candidates_wrap_into_the_box() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([1.0, 0.5], 0.1).with_periodic(true);
+    let iter = poisson.iter();
+
+    let [x, y] = wrap([1.25, -0.125], iter.period);
+    assert!((x - 0.25).abs() < 1e-6 && (y - 0.375).abs() < 1e-6);
+    assert_eq!(wrap([0.5, 0.25], iter.period), [0.5, 0.25]);
+
+    let last = [
+        cell_count(1.0, iter.cell_size) as isize - 1,
+        cell_count(0.5, iter.cell_size) as isize - 1,
+    ];
+    assert_eq!(iter.wrap_cell([-1, -1]), last);
+    assert_eq!(iter.wrap_cell([last[0] + 1, 2]), [0, 2]);
+}
+
+#[test]
+fn periodic_points_are_spaced_across_edges() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_dimensions([1.0, 0.5], 0.07)
+        .with_periodic(true)
+        .with_seed(42);
+    let points = poisson.generate();
+
+    assert!(points
+        .iter()
+        .all(|&[x, y]| (0.0..1.0).contains(&x) && (0.0..0.5).contains(&y)));
+    assert_spaced(&points, 0.07, Some([1.0, 0.5]));
+}
+
+#[test]
+fn high_dimensional_points_are_spaced() {
+    let mut poisson = Poisson::<5>::new();
+    poisson.with_dimensions([1.0; 5], 0.3).with_seed(3);
+    let points = poisson.generate();
+
+    assert_spaced(&points, 0.3, None);
+}
+
+#[test]
+fn maximal_leaves_no_gaps() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_dimensions([2.0, 1.0], 0.1)
+        .with_maximal(true)
+        .with_seed(0xFEED);
+    poisson.with_samples(2);
+    let points = poisson.generate();
+
+    assert!(largest_gap(&points, [2.0, 1.0], None) < 0.1);
+    assert_spaced(&points, 0.1, None);
+
+    // With so few samples, Bridson's algorithm alone leaves gaps behind
+    poisson.with_maximal(false);
+    assert!(largest_gap(&poisson.generate(), [2.0, 1.0], None) > 0.1);
+}
+
+#[test]
+fn maximal_periodic_leaves_no_gaps() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_periodic(true).with_maximal(true).with_seed(17);
+    poisson.with_samples(3);
+    let points = poisson.generate();
+
+    assert!(largest_gap(&points, [1.0, 1.0], Some([1.0, 1.0])) < 0.1);
+}
+
+#[test]
+fn maximal_fills_shapes() {
+    let shape = crate::Shape::Ball {
+        center: [0.5, 0.5],
+        radius: 0.5,
+    };
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_shape(shape.clone(), 0.1)
+        .with_maximal(true)
+        .with_seed(5);
+    poisson.with_samples(2);
+    let points = poisson.generate();
+
+    assert!(points.iter().all(|&p| shape.contains(p)));
+    // Probe only the inside of the ball, staying clear of slivers at its edge
+    let inner = crate::Shape::Ball {
+        center: [0.5, 0.5],
+        radius: 0.49,
+    };
+    for i in 0..100 {
+        for j in 0..100 {
+            let probe = [i as Float / 100.0, j as Float / 100.0];
+            if inner.contains(probe) {
+                assert!(points
+                    .iter()
+                    .any(|&p| crate::grid::distance_squared(probe, p, None) < 0.01));
+            }
+        }
+    }
+}
+
+#[test]
+fn maximal_high_dimensional_points_are_spaced() {
+    let mut poisson = Poisson::<4>::new();
+    poisson
+        .with_dimensions([1.0; 4], 0.3)
+        .with_periodic(true)
+        .with_seed(11);
+    let bridson = poisson.generate().len();
+    let points = poisson.with_maximal(true).generate();
+
+    assert!(points.len() > bridson);
+    assert_spaced(&points, 0.3, Some([1.0; 4]));
+}
+
+#[test]
+fn candidates_stay_in_annulus() {
+    for &candidates in &[
+        Candidates::UniformRadius,
+        Candidates::UniformArea,
+        Candidates::Boundary,
+    ] {
+        let mut poisson = Poisson3D::new();
+        poisson.with_candidates(candidates);
+        let mut iter = poisson.iter();
+
+        for _ in 0..100 {
+            let point = iter.generate_random_point([0.5; 3]);
+            let r = crate::grid::distance_squared(point, [0.5; 3], None).sqrt();
+
+            assert!(r > 0.1 && r < 0.2, "{:?}: {}", candidates, r);
+        }
+    }
+}
+
+#[test]
+fn uniform_area_favors_outer_annulus() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_candidates(Candidates::UniformArea)
+        .with_seed(8);
+    let mut iter = poisson.iter();
+
+    // The outer half of the annulus, beyond 1.5r, holds 7/12 of its area
+    let outer = (0..10_000)
+        .map(|_| iter.generate_random_point([0.5; 2]))
+        .filter(|&p| crate::grid::distance_squared(p, [0.5; 2], None) > 0.15 * 0.15)
+        .count();
+    assert!((5600..6100).contains(&outer), "{}", outer);
+}
+
+#[test]
+fn boundary_candidates_are_evenly_spaced() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_candidates(Candidates::Boundary);
+    let mut iter = poisson.iter();
+    iter.turn = 0.0;
+
+    for k in 0..30 {
+        let [x, y] = iter.generate_random_point([0.0; 2]);
+        let angle = y.atan2(x).rem_euclid(2.0 * std::f64::consts::PI as Float);
+
+        assert!((angle - k as Float * 2.0 * std::f64::consts::PI as Float / 30.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn boundary_candidates_pack_densely() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([2.0, 2.0], 0.05).with_seed(31);
+    let uniform = poisson.generate();
+    let boundary = poisson.with_candidates(Candidates::Boundary).generate();
+
+    assert!(boundary.len() > uniform.len());
+    assert_spaced(&boundary, 0.05, None);
+}
+
+/// The fraction of pairs of points generated out of order of their distance from the first point
+fn disorder(points: &[Point<2>]) -> Float {
+    let dist: Vec<Float> = points
+        .iter()
+        .map(|&p| crate::grid::distance_squared(p, points[0], None))
+        .collect();
+
+    let mut inversions = 0;
+    for (i, a) in dist.iter().enumerate() {
+        inversions += dist[i + 1..].iter().filter(|&b| b < a).count();
+    }
+
+    inversions as Float / (dist.len() * (dist.len() - 1) / 2) as Float
+}
+
+#[test]
+fn active_policies() {
+    let policies = [
+        ActivePolicy::Random,
+        ActivePolicy::Fifo,
+        ActivePolicy::Lifo,
+        ActivePolicy::OldestFirst,
+    ];
+    let seeds = 6;
+
+    // Any one distribution can buck the trend, so we compare averages over several seeds
+    let mut disorders = [0.0; 4];
+    for seed in 0..seeds {
+        let mut poisson = Poisson2D::new();
+        poisson
+            .with_dimensions([2.0, 2.0], 0.05)
+            .with_seed(seed * 7919 + 1);
+
+        for (&policy, total) in policies.iter().zip(disorders.iter_mut()) {
+            poisson.with_active_policy(policy);
+            let points = poisson.generate();
+
+            assert_eq!(points, poisson.generate(), "{:?}", policy);
+            assert!(points.len() > 1000, "{:?}", policy);
+            assert_spaced(&points, 0.05, None);
+
+            *total += disorder(&points) / seeds as Float;
+        }
+    }
+
+    // Queues spread out from the first point in order, while a stack wanders off and back
+    let [random, fifo, lifo, oldest] = disorders;
+    assert!(fifo < random * 0.75, "{:?}", disorders);
+    assert!(oldest < random * 0.75, "{:?}", disorders);
+    assert!(lifo > random * 3.0, "{:?}", disorders);
+}
+
+#[test]
+fn progressive_prefixes_are_spread_out() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([1.0, 1.0], 0.02).with_seed(2024);
+    let mut generated = poisson.generate();
+    let mut progressive = poisson.with_order(Order::Progressive).generate();
+
+    // The same points, in a different order
+    assert_ne!(generated, progressive);
+    generated.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let points = progressive.clone();
+    progressive.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(generated, progressive);
+
+    for &k in &[16, 64, 256, 1024] {
+        let prefix = &points[..k];
+        let min = min_distance(prefix, None);
+        // `k` evenly spread points are about 1/√k apart
+        let spacing = 1.0 / (k as Float).sqrt();
+        assert!(min > 0.5 * spacing, "{}: {}", k, min);
+        assert!(
+            largest_gap(prefix, [1.0, 1.0], None) < 1.5 * spacing,
+            "{}",
+            k
+        );
+    }
+}
+
+#[test]
+fn space_filling_orders() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([4.0, 4.0], 0.1).with_seed(0xCE11);
+    let mut generated = poisson.generate();
+    generated.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    // The average step between consecutive points
+    let mean_step = |points: &[Point<2>]| {
+        points
+            .windows(2)
+            .map(|w| distance_squared(w[0], w[1], None).sqrt())
+            .sum::<Float>()
+            / (points.len() - 1) as Float
+    };
+
+    let mut steps = Vec::new();
+    for &order in [Order::Morton, Order::Hilbert].iter() {
+        poisson.with_order(order);
+        let points = poisson.generate();
+
+        // The same points, reordered
+        let mut sorted = points.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(sorted, generated);
+
+        // Each point has a cell of its own, and they're already in order along the curve
+        let cells: Vec<_> = points.iter().map(|&p| poisson.cell(p)).collect();
+        let resorted = match order {
+            Order::Morton => crate::space_filling::morton_order(&cells),
+            _ => crate::space_filling::hilbert_order(&cells),
+        };
+        assert!(resorted.iter().enumerate().all(|(i, &idx)| i == idx));
+        let mut unique = cells.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), points.len());
+
+        steps.push(mean_step(&points));
+    }
+
+    // Hilbert never jumps across the space, so its steps are shorter
+    assert!(steps[1] < steps[0], "{:?}", steps);
+    assert!(steps[1] < 0.2, "{:?}", steps);
+}
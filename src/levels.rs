@@ -0,0 +1,103 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Iter, Point, Poisson};
+use crate::seeded::GOLDEN_GAMMA;
+use std::iter::FusedIterator;
+
+#[cfg(test)]
+mod tests;
+
+/// A point in a hierarchy of nested distributions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelPoint<const N: usize> {
+    /// The index of the coarsest level that contains the point
+    pub level: usize,
+    /// The position of the point
+    pub point: Point<N>,
+}
+
+/// An iterator over the points of nested distributions, from the coarsest level to the finest
+///
+/// Created by [`Poisson::iter_levels`].
+pub struct LevelIter<const N: usize> {
+    /// The distribution to fill at each level
+    distribution: Poisson<N>,
+    /// The radius of each level, largest first
+    radii: Vec<Float>,
+    /// The level currently being filled
+    level: usize,
+    /// Every point we've generated so far, across all levels
+    points: Vec<Point<N>>,
+    /// The iterator filling the current level
+    iter: Option<Iter<N>>,
+}
+
+impl<const N: usize> LevelIter<N> {
+    /// Create an iterator over the levels with the given radii
+    pub(crate) fn new(distribution: &Poisson<N>, radii: &[Float]) -> Self {
+        assert!(
+            radii
+                .iter()
+                .all(|&radius| radius > 0.0 && radius.is_finite()),
+            "The radius of each level must be finite and greater than 0"
+        );
+        assert!(
+            radii.windows(2).all(|pair| pair[0] > pair[1]),
+            "The radii of nested levels must be decreasing"
+        );
+
+        LevelIter {
+            distribution: distribution.clone(),
+            radii: radii.to_vec(),
+            level: 0,
+            points: Vec::new(),
+            iter: None,
+        }
+    }
+
+    /// Start filling the current level around the points of the coarser levels
+    fn start_level(&mut self) -> Iter<N> {
+        let mut distribution = self.distribution.clone();
+        distribution.radius = self.radii[self.level];
+        // Give each level its own random sequence, so they don't all follow the same pattern
+        distribution.seed = distribution
+            .seed
+            .map(|seed| seed.wrapping_add((self.level as u64).wrapping_mul(GOLDEN_GAMMA)));
+
+        Iter::from_points(distribution, &self.points)
+    }
+}
+
+impl<const N: usize> Iterator for LevelIter<N> {
+    type Item = LevelPoint<N>;
+
+    fn next(&mut self) -> Option<LevelPoint<N>> {
+        while self.level < self.radii.len() {
+            if self.iter.is_none() {
+                self.iter = Some(self.start_level());
+            }
+
+            if let Some(point) = self.iter.as_mut().and_then(Iterator::next) {
+                self.points.push(point);
+
+                return Some(LevelPoint {
+                    level: self.level,
+                    point,
+                });
+            }
+
+            // This level is full; move on to the next, finer one
+            self.level += 1;
+            self.iter = None;
+        }
+
+        None
+    }
+}
+
+impl<const N: usize> FusedIterator for LevelIter<N> {}
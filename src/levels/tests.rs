@@ -0,0 +1,68 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::tests::assert_spaced;
+use crate::{Float, Poisson2D, Shape};
+
+#[test]
+fn levels_are_nested() {
+    let radii = [0.2, 0.1, 0.05];
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(0xD00D);
+    let points: Vec<_> = poisson.iter_levels(&radii).collect();
+
+    // Levels come in order, and each is spaced by its own radius
+    assert!(points.windows(2).all(|pair| pair[0].level <= pair[1].level));
+    for (level, &radius) in radii.iter().enumerate() {
+        let nested: Vec<_> = points.iter().filter(|p| p.level <= level).collect();
+        assert!(nested.iter().any(|p| p.level == level));
+
+        let nested: Vec<_> = nested.iter().map(|p| p.point).collect();
+        assert_spaced(&nested, radius, None);
+    }
+
+    // The finest level fills the space about as densely as a plain distribution would
+    poisson.with_radius(0.05);
+    let plain = poisson.generate().len() as f64;
+    assert!((points.len() as f64) > 0.9 * plain);
+}
+
+#[test]
+fn levels_are_deterministic() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_shape(
+            Shape::Ball {
+                center: [0.0, 0.0],
+                radius: 1.0,
+            },
+            0.1,
+        )
+        .with_seed(3);
+
+    let first: Vec<_> = poisson.iter_levels(&[0.3, 0.1]).collect();
+    let second: Vec<_> = poisson.iter_levels(&[0.3, 0.1]).collect();
+    assert_eq!(first, second);
+}
+
+#[test]
+#[should_panic]
+fn radii_must_decrease() {
+    let _ = Poisson2D::new().iter_levels(&[0.1, 0.2]);
+}
+
+#[test]
+#[should_panic]
+fn radii_must_be_positive() {
+    let _ = Poisson2D::new().iter_levels(&[0.1, 0.0]);
+}
+
+#[test]
+#[should_panic]
+fn radii_must_be_finite() {
+    let _ = Poisson2D::new().iter_levels(&[Float::INFINITY, 0.1]);
+}
@@ -33,6 +33,8 @@
 //!  * `derive_serde` automatically derives Serde's Serialize and Deserialize traits for `Poisson`,
 //!    This relies on the [`serde_arrays`][sa] crate to allow (de)serializing the const generic arrays
 //!    used by `Poisson`.
+//!  * `geometry` adds `Poisson::triangulate`, which computes the Delaunay triangulation of a 2D
+//!    distribution and the Voronoi cells around its points.
 //!
 //! # Requirements
 //!
@@ -96,6 +98,14 @@
 //! let points_7d = points_7d.iter();
 //! ```
 //!
+//! Besides boxes, distributions can fill balls, ellipsoids, simplices, cylinders and capsules:
+//! ```
+//! use fast_poisson::{Poisson3D, Shape};
+//!
+//! let sphere = Shape::Ball { center: [0.0; 3], radius: 10.0 };
+//! let points = Poisson3D::new().with_shape(sphere, 1.0).iter();
+//! ```
+//!
 //! # Upgrading
 //!
 //! ## 0.4.x
@@ -154,13 +164,48 @@
 //! [small_rng]: https://docs.rs/rand/0.8.3/rand/rngs/struct.SmallRng.html
 //! [sa]: https://crates.io/crates/serde_arrays
 
+use iter::Rand;
+use rand::SeedableRng;
 #[cfg(feature = "derive_serde")]
 use serde::{Deserialize, Serialize};
 #[cfg(test)]
 mod tests;
 
+pub mod analysis;
+mod classes;
+pub use classes::{ClassIter, ClassPoint, Classes};
+mod curve;
+pub use curve::{Curve, CurveIter, CurvePoint};
+mod elimination;
+#[cfg(feature = "geometry")]
+mod geometry;
+#[cfg(feature = "geometry")]
+pub use geometry::Triangulation;
+mod graph;
+pub use graph::NeighborGraph;
+mod grid;
+mod index;
+pub use index::SpatialIndex;
 mod iter;
-pub use iter::{Iter, Point};
+pub use iter::{ActivePolicy, Candidates, Cell, Iter, Order, Point};
+mod levels;
+pub use levels::{LevelIter, LevelPoint};
+mod mesh;
+pub use mesh::{Containment, Mesh, Spacing, SurfaceIter, SurfacePoint};
+mod noise;
+pub use noise::{Distance, Worley};
+mod optimize;
+pub use optimize::Convergence;
+mod parametric;
+pub use parametric::{ParametricIter, ParametricPoint};
+mod seeded;
+pub use seeded::{SeededIter, SeededPoint};
+mod set;
+pub use set::PoissonSet;
+mod shape;
+pub use shape::Shape;
+mod space_filling;
+mod vector;
 
 /// [`Poisson`] disk distribution in 2 dimensions
 pub type Poisson2D = Poisson<2>;
@@ -192,12 +237,33 @@ pub struct Poisson<const N: usize> {
     /// Dimensions of the box
     #[cfg_attr(feature = "derive_serde", serde(with = "serde_arrays"))]
     dimensions: [Float; N],
+    /// Shape to fill instead of the box, if any
+    #[cfg_attr(feature = "derive_serde", serde(default))]
+    shape: Option<Shape<N>>,
+    /// Whether the box wraps around in every dimension
+    #[cfg_attr(feature = "derive_serde", serde(default))]
+    periodic: bool,
+    /// Whether to keep filling gaps until no more points fit
+    #[cfg_attr(feature = "derive_serde", serde(default))]
+    maximal: bool,
     /// Radius around each point that must remain empty
     radius: Float,
     /// Seed to use for the internal RNG
     seed: Option<u64>,
     /// Number of samples to generate and test around each point
     num_samples: u32,
+    /// How the samples around each point are placed
+    #[cfg_attr(feature = "derive_serde", serde(default))]
+    candidates: Candidates,
+    /// Which active point to grow the distribution from next
+    #[cfg_attr(feature = "derive_serde", serde(default))]
+    active_policy: ActivePolicy,
+    /// The order in which points are returned
+    #[cfg_attr(feature = "derive_serde", serde(default))]
+    order: Order,
+    /// The classes of points to mix in a multi-class distribution
+    #[cfg_attr(feature = "derive_serde", serde(default))]
+    classes: Classes,
 }
 
 impl<const N: usize> Poisson<N> {
@@ -238,6 +304,102 @@ impl<const N: usize> Poisson<N> {
     /// ```
     pub fn with_dimensions(&mut self, dimensions: [Float; N], radius: Float) -> &mut Self {
         self.dimensions = dimensions;
+        self.shape = None;
+        self.radius = radius;
+
+        self
+    }
+
+    /// Specify a [`Shape`] to be filled, instead of a box, and the radius around each point
+    ///
+    /// Candidate points are tested against the exact shape, so the distribution stays evenly
+    /// spaced right up to curved walls. The shape replaces any box previously set with
+    /// [`Poisson::with_dimensions`].
+    ///
+    /// To generate a 3-dimensional distribution in a capsule, with no points closer than 0.2:
+    /// ```
+    /// # use fast_poisson::{Poisson3D, Shape};
+    /// let capsule = Shape::Capsule {
+    ///     start: [0.0, 0.0, 0.0],
+    ///     end: [0.0, 0.0, 2.0],
+    ///     radius: 0.5,
+    /// };
+    /// let mut points = Poisson3D::new().with_shape(capsule.clone(), 0.2).iter();
+    ///
+    /// assert!(points.all(|p| capsule.contains(p)));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if given a [`Shape::Simplex`] that does not have exactly N + 1 vertices.
+    pub fn with_shape(&mut self, shape: Shape<N>, radius: Float) -> &mut Self {
+        if let Shape::Simplex { vertices } = &shape {
+            assert_eq!(
+                vertices.len(),
+                N + 1,
+                "A simplex in {} dimensions must have {} vertices",
+                N,
+                N + 1
+            );
+        }
+
+        self.shape = Some(shape);
+        self.radius = radius;
+
+        self
+    }
+
+    /// Specify whether the box wraps around, like the surface of a torus
+    ///
+    /// In a periodic distribution, points near one side of the box keep their distance from
+    /// points near the opposite side too, so that copies of the distribution can be tiled
+    /// seamlessly. This only applies to the box set by [`Poisson::with_dimensions`], not to a
+    /// [`Shape`].
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let tile = Poisson2D::new().with_periodic(true).generate();
+    /// ```
+    pub fn with_periodic(&mut self, periodic: bool) -> &mut Self {
+        self.periodic = periodic;
+
+        self
+    }
+
+    /// Specify whether to fill the distribution until no more points fit
+    ///
+    /// Bridson's algorithm gives up on each point after [`num_samples`](Poisson::with_samples)
+    /// failed attempts to place a neighbor, which can leave gaps wider than `2 * radius`. A
+    /// maximal distribution goes back afterward to find and fill those gaps, so that every part
+    /// of the space is within `radius` of some point.
+    ///
+    /// This costs more the more dimensions there are, as each gap that's left is split into 2^N
+    /// smaller ones. Filling the gaps takes about as long again as Bridson's algorithm in 2D,
+    /// several times as long in 3D, and 20 times as long or more from 4D up.
+    ///
+    /// Gaps are found by splitting the space into ever smaller boxes, so along the curved edges
+    /// of a [`Shape`] slivers smaller than those boxes may be left uncovered.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let points = Poisson2D::new().with_maximal(true).generate();
+    /// ```
+    pub fn with_maximal(&mut self, maximal: bool) -> &mut Self {
+        self.maximal = maximal;
+
+        self
+    }
+
+    /// Specify the radius around each point, keeping the space to be filled
+    ///
+    /// This is mostly useful for distributions that don't fill a box or [`Shape`], such as those
+    /// on the surface of a [`Mesh`].
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson3D;
+    /// let points = Poisson3D::new().with_radius(0.2).iter();
+    /// ```
+    pub fn with_radius(&mut self, radius: Float) -> &mut Self {
         self.radius = radius;
 
         self
@@ -276,6 +438,164 @@ impl<const N: usize> Poisson<N> {
         self
     }
 
+    /// Specify how the samples around each point are placed
+    ///
+    /// [`Candidates::Boundary`] is usually the best choice: even with far fewer
+    /// [samples](Poisson::with_samples) around each point, it fills the space more densely than
+    /// the default [`Candidates::UniformRadius`], and so in less time.
+    ///
+    /// ```
+    /// # use fast_poisson::{Candidates, Poisson2D};
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_candidates(Candidates::Boundary).with_samples(8);
+    /// let points = poisson.generate();
+    /// ```
+    pub fn with_candidates(&mut self, candidates: Candidates) -> &mut Self {
+        self.candidates = candidates;
+
+        self
+    }
+
+    /// Specify which active point to grow the distribution from next
+    ///
+    /// This only changes the order in which points are generated, which is what you see when
+    /// drawing them as they arrive; for example, [`ActivePolicy::Fifo`] grows the distribution
+    /// outward from the first point as an even frontier. With a seed the order is still
+    /// deterministic.
+    ///
+    /// ```
+    /// # use fast_poisson::{ActivePolicy, Poisson2D};
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_active_policy(ActivePolicy::Fifo);
+    ///
+    /// for point in poisson.iter() {
+    ///     // Draw the next point on the frontier
+    /// }
+    /// ```
+    pub fn with_active_policy(&mut self, active_policy: ActivePolicy) -> &mut Self {
+        self.active_policy = active_policy;
+
+        self
+    }
+
+    /// Specify the order in which the points are returned
+    ///
+    /// With [`Order::Progressive`], every prefix of the distribution is spread evenly across the
+    /// whole space, so taking just the first few points gives a sparser distribution rather
+    /// than a cluster in one corner. [`Order::Morton`] and [`Order::Hilbert`] sort the points
+    /// along a space-filling curve instead, so that points near each other in the order are near
+    /// each other in space; [`Poisson::cell`] gives the cell each one is sorted by.
+    ///
+    /// ```
+    /// # use fast_poisson::{Order, Poisson2D};
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_order(Order::Progressive);
+    ///
+    /// let coarse: Vec<_> = poisson.iter().take(10).collect();
+    /// ```
+    pub fn with_order(&mut self, order: Order) -> &mut Self {
+        self.order = order;
+
+        self
+    }
+
+    /// Specify the classes of points to mix in a multi-class distribution
+    ///
+    /// Each class has its own radius and share of the points, and points of different classes
+    /// are kept apart by the spacing set in [`Classes`]. The classes are only used by
+    /// [`Poisson::iter_classes`].
+    ///
+    /// ```
+    /// # use fast_poisson::{Classes, Poisson2D};
+    /// let mut classes = Classes::new();
+    /// classes.with_class(0.2, 1.0).with_class(0.1, 3.0);
+    ///
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_classes(classes);
+    /// ```
+    pub fn with_classes(&mut self, classes: Classes) -> &mut Self {
+        self.classes = classes;
+
+        self
+    }
+
+    /// Returns an iterator over a distribution mixing the [classes](Poisson::with_classes) of
+    /// points
+    ///
+    /// All classes are generated together, so none of them is biased by having to fit around the
+    /// others afterward. Each point is reported along with its class. The distribution's own
+    /// radius is ignored, and without any classes there are no points.
+    ///
+    /// ```
+    /// # use fast_poisson::{Classes, Poisson2D};
+    /// let mut classes = Classes::new();
+    /// classes.with_class(0.2, 1.0).with_class(0.1, 3.0);
+    ///
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_classes(classes);
+    ///
+    /// for plant in poisson.iter_classes() {
+    ///     let kind = if plant.class == 0 { "tree" } else { "bush" };
+    ///     println!("{} at {:?}", kind, plant.point);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn iter_classes(&self) -> ClassIter<N> {
+        ClassIter::new(self)
+    }
+
+    /// Returns an iterator over nested distributions with ever smaller radii
+    ///
+    /// The first level is an ordinary distribution with radius `radii[0]`. Each following level
+    /// keeps all of the points of the levels before it, and fills the space between them until
+    /// no two points are closer than its own radius. Every point is tagged with the level at
+    /// which it first appeared, so the points of levels `0..=k` together make up a complete
+    /// distribution with radius `radii[k]`. The distribution's own radius is ignored.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let trees = Poisson2D::new().iter_levels(&[0.2, 0.1, 0.05]);
+    ///
+    /// // Draw only the trees at or above the current level of detail
+    /// let detail = 1;
+    /// for tree in trees.filter(|tree| tree.level <= detail) {
+    ///     println!("{:?}", tree.point);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics unless the radii are finite, greater than 0 and strictly decreasing.
+    #[must_use]
+    pub fn iter_levels(&self, radii: &[Float]) -> LevelIter<N> {
+        LevelIter::new(self, radii)
+    }
+
+    /// Returns an iterator over the points of a distribution along a [`Curve`]
+    ///
+    /// Points are spread along the whole curve, no two of them closer than the radius as measured
+    /// by `spacing`: [`Spacing::Geodesic`] measures the arc length between points, while
+    /// [`Spacing::Euclidean`] keeps points apart through space too, so they don't crowd together
+    /// where the curve bends back on itself. Each point is reported along with its arc length and
+    /// the curve's tangent. The dimensions or shape of the distribution are ignored; use
+    /// [`Poisson::with_radius`] to set the radius. A radius of zero yields no points at all.
+    ///
+    /// ```
+    /// # use fast_poisson::{Curve, Poisson2D, Spacing};
+    /// let river = Curve::bezier(&[[0.0, 0.0], [5.0, 10.0], [10.0, -10.0], [15.0, 0.0]]);
+    ///
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_radius(1.0);
+    ///
+    /// for rock in poisson.iter_curve(&river, Spacing::Euclidean) {
+    ///     println!("{:?} heading {:?}", rock.point, rock.tangent);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn iter_curve<'a>(&self, curve: &'a Curve<N>, spacing: Spacing) -> CurveIter<'a, N> {
+        CurveIter::new(self, curve, spacing)
+    }
+
     /// Returns an iterator over the points in this distribution
     ///
     /// ```
@@ -291,6 +611,94 @@ impl<const N: usize> Poisson<N> {
         Iter::new(self.clone())
     }
 
+    /// Returns an iterator over new points that carry on a distribution from points already in
+    /// place
+    ///
+    /// The new points grow out from the existing ones, and keep the radius from them as well as
+    /// from each other. The existing points aren't returned. Any that lie outside the space, or
+    /// closer than the radius to one earlier in the slice, are left out and may end up with new
+    /// points close to them.
+    ///
+    /// To carry on from points made elsewhere, see [`analysis::estimate`] to find their radius.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_dimensions([1.0, 1.0], 0.1);
+    /// let left: Vec<_> = poisson.iter().filter(|p| p[0] < 0.5).collect();
+    ///
+    /// poisson.with_dimensions([2.0, 1.0], 0.1);
+    /// let right: Vec<_> = poisson.iter_from(&left).collect();
+    /// ```
+    #[must_use]
+    pub fn iter_from(&self, points: &[Point<N>]) -> Iter<N> {
+        Iter::from_points(self.clone(), points)
+    }
+
+    /// The coordinates of the cell of the grid holding the point
+    ///
+    /// The grid starts at the lowest corner of the box (or the [`Shape`]'s bounding box), and its
+    /// cells are `radius / sqrt(N)` wide, so that no two points of the distribution share a cell.
+    /// Points within the space have cells with coordinates of 0 or more.
+    ///
+    /// This is the grid used for the distribution's own box or shape. The points of
+    /// [`Poisson::iter_volume`] are laid out on a grid starting at the mesh's bounding box
+    /// instead, so their cells won't match the ones given here.
+    ///
+    /// ```
+    /// # use fast_poisson::{Order, Poisson2D};
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_order(Order::Hilbert);
+    ///
+    /// // Points and their cells, ready for a spatial database
+    /// let rows: Vec<_> = poisson.iter().map(|point| (poisson.cell(point), point)).collect();
+    /// ```
+    pub fn cell(&self, point: Point<N>) -> Cell<N> {
+        let (origin, _) = self.bounds();
+        let cell_size = self.radius / (N as Float).sqrt();
+
+        let mut cell = [0; N];
+        for i in 0..N {
+            cell[i] = ((point[i] - origin[i]) / cell_size).floor() as isize;
+        }
+
+        cell
+    }
+
+    /// Generate the points in this Poisson distribution, indexed for finding the points near any
+    /// position
+    ///
+    /// See [`SpatialIndex`] for the queries it answers.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let index = Poisson2D::new().index();
+    ///
+    /// for neighbor in index.within_radius([0.5, 0.5], 0.2) {
+    ///     println!("{:?}", neighbor);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn index(&self) -> SpatialIndex<N> {
+        self.iter().into_index()
+    }
+
+    /// Generate the points in this Poisson distribution, to use as the feature points of Worley
+    /// noise
+    ///
+    /// See [`Worley`] for the details.
+    ///
+    /// ```
+    /// # use fast_poisson::{Distance, Poisson2D};
+    /// let noise = Poisson2D::new().worley();
+    ///
+    /// let value = noise.sample([0.5, 0.5], Distance::F1);
+    /// ```
+    #[must_use]
+    pub fn worley(&self) -> Worley<N> {
+        Worley::new(self)
+    }
+
     /// Generate the points in this Poisson distribution, collected into a [`Vec`](std::vec::Vec).
     ///
     /// Note that this method does *not* consume the `Poisson`, so you can call it multiple times
@@ -362,6 +770,317 @@ impl<const N: usize> Poisson<N> {
     {
         self.iter().map(|point| point.into()).collect()
     }
+
+    /// Generate the points in the Poisson distribution, linking every pair of them closer than
+    /// `factor * radius` to each other
+    ///
+    /// The links are found with a grid rather than by comparing every pair of points. Since no
+    /// two points are closer than `radius`, a `factor` of 1 or less links nothing. At 2 or more a
+    /// point is only left without neighbors if there's room beside it for another point, which
+    /// [`Poisson::with_maximal`] rules out.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let graph = Poisson2D::new().neighbor_graph(1.5);
+    ///
+    /// for (a, b) in graph.edges() {
+    ///     println!("{:?} -- {:?}", graph.points[a], graph.points[b]);
+    /// }
+    /// ```
+    pub fn neighbor_graph(&self, factor: Float) -> NeighborGraph<N> {
+        NeighborGraph::new(self, self.generate(), factor * self.radius)
+    }
+
+    /// Reduce an existing set of points to exactly `count` points with Poisson disk properties
+    ///
+    /// This uses Yuksel's weighted sample elimination: every point is weighted by how closely it
+    /// is crowded by its neighbors, and the most crowded point is removed until only `count`
+    /// remain. The input can be anything covering the space fairly evenly, such as uniformly
+    /// random points or the vertices of a mesh; for best results it should have at least
+    /// 3 to 5 times as many points as you want to keep.
+    ///
+    /// The points are assumed to lie within the distribution's space, whose volume decides how
+    /// far apart the kept points can be, and distances wrap around a
+    /// [periodic](Poisson::with_periodic) box. The distribution's radius is not used. If there
+    /// are no more than `count` points they are all kept.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// # use rand::prelude::*;
+    /// let mut rng = rand::thread_rng();
+    /// let random: Vec<_> = (0..500).map(|_| [rng.gen(), rng.gen()]).collect();
+    ///
+    /// let points = Poisson2D::new().eliminate(&random, 100);
+    /// assert_eq!(points.len(), 100);
+    /// ```
+    pub fn eliminate(&self, points: &[Point<N>], count: usize) -> Vec<Point<N>> {
+        elimination::eliminate(&elimination::Space::of(self), points, count)
+            .into_iter()
+            .map(|idx| points[idx])
+            .collect()
+    }
+
+    /// Generate a distribution of exactly `count` points
+    ///
+    /// Bridson's algorithm can't promise how many points it will produce. This instead generates
+    /// uniformly random points throughout the space, using the distribution's seed, and then
+    /// reduces them with [`Poisson::eliminate`]. The distribution's radius is not used.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_dimensions([2.0, 1.0], 0.0).with_seed(1337);
+    ///
+    /// assert_eq!(poisson.generate_count(250).len(), 250);
+    /// ```
+    pub fn generate_count(&self, count: usize) -> Vec<Point<N>> {
+        let candidates = count.saturating_mul(elimination::CANDIDATES_PER_POINT);
+        let points = elimination::random_points(self, candidates);
+
+        self.eliminate(&points, count)
+    }
+
+    /// Move the points to spread them out as far from each other as possible
+    ///
+    /// This is Schlömer's farthest-point optimization: each pass moves every point in turn to
+    /// the spot furthest from all of the others that can be found, which never lowers the
+    /// smallest distance between any two points. Passes continue until the points stop
+    /// improving or `iterations` passes have been made, and the result reports how far they got.
+    /// The points must lie within the distribution's space; distances wrap around a
+    /// [periodic](Poisson::with_periodic) box. The distribution's radius is not used.
+    ///
+    /// This is slow, but produces the highest quality distributions, e.g. for precomputed
+    /// sampling patterns.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_periodic(true).with_seed(7);
+    /// let mut points = poisson.generate();
+    ///
+    /// let result = poisson.optimize(&mut points, 50);
+    /// println!("{} passes, min distance {}", result.iterations, result.min_distance);
+    /// ```
+    pub fn optimize(&self, points: &mut [Point<N>], iterations: usize) -> Convergence {
+        optimize::optimize(self, points, iterations)
+    }
+
+    /// Move each point toward the center of the space closest to it, evening out the spacing
+    ///
+    /// This is Lloyd's relaxation toward a centroidal Voronoi tessellation: each pass estimates
+    /// each point's Voronoi cell by scattering random samples over the distribution's space,
+    /// then moves the point to the centroid of its cell. Passes continue until no point moves
+    /// as far as `tolerance`, or `iterations` passes have been made. The result is more regular
+    /// than a Poisson disk distribution, but still irregular enough to look random after a few
+    /// passes; many passes converge toward a hexagonal grid. Distances wrap around a
+    /// [periodic](Poisson::with_periodic) box. The distribution's radius is not used.
+    ///
+    /// The cells are only estimated, so points keep jittering by a small fraction of the spacing
+    /// between them; a `tolerance` smaller than that won't be met.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_seed(7);
+    /// let mut points = poisson.generate();
+    ///
+    /// let result = poisson.relax(&mut points, 10, 0.01);
+    /// ```
+    pub fn relax(
+        &self,
+        points: &mut [Point<N>],
+        iterations: usize,
+        tolerance: Float,
+    ) -> Convergence {
+        optimize::relax(self, points, iterations, tolerance)
+    }
+
+    /// The lowest corner and the size of the axis-aligned box holding the space to be filled
+    pub(crate) fn bounds(&self) -> (Point<N>, [Float; N]) {
+        match &self.shape {
+            None => ([0.0; N], self.dimensions),
+            Some(shape) => {
+                let (min, max) = shape.bounds();
+                let mut size = [0.0; N];
+                for i in 0..N {
+                    size[i] = max[i] - min[i];
+                }
+
+                (min, size)
+            }
+        }
+    }
+
+    /// A new RNG from the distribution's seed, or seeded non-deterministically if it has none
+    pub(crate) fn rng(&self) -> Rand {
+        match self.seed {
+            None => Rand::from_entropy(),
+            Some(seed) => Rand::seed_from_u64(seed),
+        }
+    }
+
+    /// The size of the box if it wraps around, or `None` if it doesn't
+    pub(crate) fn period(&self) -> Option<[Float; N]> {
+        if self.periodic && self.shape.is_none() {
+            Some(self.dimensions)
+        } else {
+            None
+        }
+    }
+
+    /// The N-dimensional volume of the space to be filled
+    pub(crate) fn volume(&self) -> Float {
+        match &self.shape {
+            None => self.dimensions.iter().product(),
+            Some(shape) => shape.volume(),
+        }
+    }
+
+    /// Returns true if the point lies within the space to be filled
+    ///
+    /// Points must lie within the semi-open range of [`Poisson::bounds`] even for shapes, so that
+    /// every point maps onto a cell of the grid.
+    pub(crate) fn contains(&self, point: Point<N>) -> bool {
+        let (origin, size) = self.bounds();
+        let in_bounds = (0..N).all(|i| point[i] >= origin[i] && point[i] < origin[i] + size[i]);
+
+        match &self.shape {
+            None => in_bounds,
+            Some(shape) => in_bounds && shape.contains(point),
+        }
+    }
+}
+
+impl Poisson<2> {
+    /// Returns an iterator over the points of a distribution on a parametric surface
+    ///
+    /// The `map` takes parameters `[u, v]` from this distribution's space, usually a rectangle
+    /// set with [`Poisson::with_dimensions`] or a [`Shape::Cuboid`], to points on the surface.
+    /// Points are generated in parameter space, but the radius is enforced between the points on
+    /// the surface itself, so they stay evenly spaced however much the map stretches or squashes
+    /// the parameters. Each point is reported with both its parameters and its position.
+    ///
+    /// Candidates are always drawn as with [`Candidates::UniformRadius`], and points to grow
+    /// from are always picked as with [`ActivePolicy::Random`]. The settings from
+    /// [`Poisson::with_candidates`], [`Poisson::with_active_policy`] and
+    /// [`Poisson::with_maximal`] are ignored, so gaps may be left that could still fit a point.
+    ///
+    /// A torus, whose parameters wrap around in both directions:
+    /// ```
+    /// # use fast_poisson::{Poisson2D, Shape};
+    /// # #[cfg(not(feature = "single_precision"))]
+    /// # type Float = f64;
+    /// # #[cfg(feature = "single_precision")]
+    /// # type Float = f32;
+    /// use std::f64::consts::PI;
+    /// let tau = 2.0 * PI as Float;
+    ///
+    /// let torus = |[u, v]: [Float; 2]| {
+    ///     let ring = 3.0 + v.cos();
+    ///     [ring * u.cos(), ring * u.sin(), v.sin()]
+    /// };
+    ///
+    /// let mut poisson = Poisson2D::new();
+    /// poisson.with_dimensions([tau, tau], 0.5);
+    ///
+    /// for sample in poisson.iter_parametric(torus) {
+    ///     println!("{:?} maps to {:?}", sample.uv, sample.point);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn iter_parametric<F>(&self, map: F) -> ParametricIter<F>
+    where
+        F: Fn([Float; 2]) -> [Float; 3],
+    {
+        ParametricIter::new(self, map)
+    }
+
+    /// Generate the points in the Poisson distribution, with their Delaunay triangulation
+    ///
+    /// The triangulation is built with the Bowyer–Watson algorithm, and also gives the Voronoi
+    /// cell around each point, clipped to the box given to [`Poisson::with_dimensions`] (or the
+    /// bounding box of the [`Shape`]). A periodic box is triangulated as if it didn't wrap around.
+    ///
+    /// Requires the `geometry` feature.
+    ///
+    /// ```
+    /// # use fast_poisson::Poisson2D;
+    /// let triangulation = Poisson2D::new().triangulate();
+    ///
+    /// for [a, b, c] in triangulation.triangles.iter() {
+    ///     println!("{:?}", [triangulation.points[*a], triangulation.points[*b], triangulation.points[*c]]);
+    /// }
+    /// let cells = triangulation.voronoi_cells();
+    /// ```
+    #[cfg(feature = "geometry")]
+    pub fn triangulate(&self) -> Triangulation {
+        Triangulation::new(self.generate(), self.bounds())
+    }
+}
+
+impl Poisson<3> {
+    /// Returns an iterator over the points of a distribution on the surface of a [`Mesh`]
+    ///
+    /// Points are spread over the whole surface, no two of them closer than the radius as
+    /// measured by `spacing`. Each point is reported along with the triangle it lies on and its
+    /// barycentric coordinates within that triangle. The dimensions or shape of the distribution
+    /// are ignored; use [`Poisson::with_radius`] to set the radius. A radius of zero yields no
+    /// points at all.
+    ///
+    /// ```
+    /// # use fast_poisson::{Mesh, Poisson3D, Spacing};
+    /// // A tetrahedron
+    /// let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    /// let indices = [0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3];
+    /// let mesh = Mesh::new(&vertices, &indices);
+    ///
+    /// let mut poisson = Poisson3D::new();
+    /// poisson.with_radius(0.1);
+    ///
+    /// for sample in poisson.iter_surface(&mesh, Spacing::Euclidean) {
+    ///     println!("{:?} on triangle {}", sample.point, sample.triangle);
+    /// }
+    /// ```
+    ///
+    /// Unlike [`Poisson::iter`] this throws darts at the whole surface rather than growing the
+    /// distribution out from a single point, since a mesh may be made of many disconnected parts.
+    /// A larger number of [samples](Poisson::with_samples) fills the surface more completely.
+    #[must_use]
+    pub fn iter_surface<'a>(&self, mesh: &'a Mesh, spacing: Spacing) -> SurfaceIter<'a> {
+        SurfaceIter::new(self, mesh, spacing)
+    }
+
+    /// Returns an iterator over the points of a distribution filling the inside of a closed
+    /// [`Mesh`]
+    ///
+    /// This works just like [`Poisson::iter`] filling a box, except that the space is bounded by
+    /// the mesh; `containment` chooses how points are tested against it. The dimensions or shape
+    /// of the distribution are ignored; use [`Poisson::with_radius`] to set the radius.
+    ///
+    /// ```
+    /// # use fast_poisson::{Containment, Mesh, Poisson3D};
+    /// // A tetrahedron
+    /// let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    /// let indices = [0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3];
+    /// let mesh = Mesh::new(&vertices, &indices);
+    ///
+    /// let mut poisson = Poisson3D::new();
+    /// poisson.with_radius(0.1);
+    ///
+    /// let mut points = poisson.iter_volume(&mesh, Containment::RayParity);
+    /// assert!(points.all(|[x, y, z]| x + y + z <= 1.0));
+    /// ```
+    #[must_use]
+    pub fn iter_volume(&self, mesh: &Mesh, containment: Containment) -> Iter<3> {
+        let bounds = mesh.bounds();
+        let volume = mesh::Volume::new(mesh.clone(), containment);
+
+        Iter::in_region(
+            self.clone(),
+            bounds,
+            Box::new(move |point| volume.contains(point)),
+        )
+    }
 }
 
 /// No object is equal, not even to itself, if the seed is unspecified
@@ -370,9 +1089,16 @@ impl<const N: usize> PartialEq for Poisson<N> {
         self.seed.is_some()
             && other.seed.is_some()
             && self.dimensions == other.dimensions
+            && self.shape == other.shape
+            && self.periodic == other.periodic
+            && self.maximal == other.maximal
             && self.radius == other.radius
             && self.seed == other.seed
             && self.num_samples == other.num_samples
+            && self.candidates == other.candidates
+            && self.active_policy == other.active_policy
+            && self.order == other.order
+            && self.classes == other.classes
     }
 }
 
@@ -380,9 +1106,16 @@ impl<const N: usize> Default for Poisson<N> {
     fn default() -> Self {
         Poisson::<N> {
             dimensions: [1.0; N],
+            shape: None,
+            periodic: false,
+            maximal: false,
             radius: 0.1,
             seed: None,
             num_samples: 30,
+            candidates: Candidates::UniformRadius,
+            active_policy: ActivePolicy::Random,
+            order: Order::Generation,
+            classes: Classes::new(),
         }
     }
 }
@@ -0,0 +1,523 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Point, Poisson};
+use crate::grid::{partition_point, Buckets, Keyed};
+use crate::iter::Rand;
+use crate::vector::{cross, distance, dot, norm, sub};
+use rand::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::iter::FusedIterator;
+
+#[cfg(test)]
+mod tests;
+
+/// A triangle mesh built from vertex and index buffers
+///
+/// ```
+/// # use fast_poisson::Mesh;
+/// // A unit square made of two triangles
+/// let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+/// let indices = [0, 1, 2, 0, 2, 3];
+///
+/// let mesh = Mesh::new(&vertices, &indices);
+/// assert_eq!(mesh.triangles().len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mesh {
+    /// The position of each vertex
+    vertices: Vec<Point<3>>,
+    /// The vertex indices of each triangle
+    triangles: Vec<[usize; 3]>,
+}
+
+impl Mesh {
+    /// Create a mesh from a vertex buffer and an index buffer
+    ///
+    /// Every three consecutive indices form one triangle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of indices is not a multiple of 3, or if any index is out of range of
+    /// the vertex buffer.
+    pub fn new(vertices: &[Point<3>], indices: &[u32]) -> Self {
+        assert!(
+            indices.chunks_exact(3).remainder().is_empty(),
+            "The number of indices must be a multiple of 3"
+        );
+        assert!(
+            indices.iter().all(|&i| (i as usize) < vertices.len()),
+            "Every index must refer to a vertex"
+        );
+
+        let triangles = indices
+            .chunks_exact(3)
+            .map(|tri| [tri[0] as usize, tri[1] as usize, tri[2] as usize])
+            .collect();
+
+        Mesh {
+            vertices: vertices.to_vec(),
+            triangles,
+        }
+    }
+
+    /// The position of each vertex
+    pub fn vertices(&self) -> &[Point<3>] {
+        &self.vertices
+    }
+
+    /// The vertex indices of each triangle
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// The positions of the corners of a triangle
+    pub(crate) fn corners(&self, triangle: usize) -> [Point<3>; 3] {
+        let [a, b, c] = self.triangles[triangle];
+
+        [self.vertices[a], self.vertices[b], self.vertices[c]]
+    }
+
+    /// The area of a triangle
+    fn area(&self, triangle: usize) -> Float {
+        let [a, b, c] = self.corners(triangle);
+
+        norm(cross(sub(b, a), sub(c, a))) / 2.0
+    }
+
+    /// The lowest corner and the size of the axis-aligned box holding the mesh
+    pub(crate) fn bounds(&self) -> (Point<3>, [Float; 3]) {
+        if self.vertices.is_empty() {
+            return ([0.0; 3], [0.0; 3]);
+        }
+
+        let mut min = [Float::INFINITY; 3];
+        let mut max = [Float::NEG_INFINITY; 3];
+        for vertex in &self.vertices {
+            for i in 0..3 {
+                min[i] = min[i].min(vertex[i]);
+                max[i] = max[i].max(vertex[i]);
+            }
+        }
+
+        (min, sub(max, min))
+    }
+
+    /// The neighbors of each vertex, along with the length of the edge leading to them
+    fn edges(&self) -> Vec<Vec<(usize, Float)>> {
+        let mut edges = vec![Vec::new(); self.vertices.len()];
+
+        for tri in &self.triangles {
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                let length = distance(self.vertices[a], self.vertices[b]);
+
+                edges[a].push((b, length));
+                edges[b].push((a, length));
+            }
+        }
+
+        edges
+    }
+}
+
+/// How the spacing between points on a surface or curve is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spacing {
+    /// Straight-line distance through space
+    Euclidean,
+    /// Distance along the surface or curve itself
+    ///
+    /// On a [`Mesh`] this is approximated by the shortest path through the mesh's edges, so it
+    /// lets points on opposite sides of a thin wall sit closer together than the radius. On a
+    /// [`Curve`](crate::Curve) it is the arc length between the points.
+    Geodesic,
+}
+
+/// A point on the surface of a [`Mesh`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfacePoint {
+    /// The position of the point
+    pub point: Point<3>,
+    /// The index of the triangle the point lies on
+    pub triangle: usize,
+    /// The barycentric coordinates of the point with respect to the triangle's corners
+    pub barycentric: [Float; 3],
+}
+
+/// An iterator over the points in a Poisson disk distribution on the surface of a [`Mesh`]
+///
+/// Created by [`Poisson::iter_surface`].
+pub struct SurfaceIter<'a> {
+    /// The mesh we're sampling
+    mesh: &'a Mesh,
+    /// How to measure the spacing between points
+    spacing: Spacing,
+    /// Radius around each point that must remain empty
+    radius: Float,
+    /// The RNG
+    rng: Rand,
+    /// The grid stores spatially-oriented samples for fast checking of neighboring sample points
+    grid: Buckets<3>,
+    /// Every point we've generated so far
+    points: Vec<SurfacePoint>,
+    /// The running total of the triangles' areas, used to pick triangles in proportion to area
+    cumulative_area: Vec<Float>,
+    /// The edges leading out of each vertex, needed for geodesic spacing
+    edges: Vec<Vec<(usize, Float)>>,
+    /// The number of random samples we have left to try
+    remaining: usize,
+}
+
+impl<'a> SurfaceIter<'a> {
+    /// Create an iterator over the surface of the mesh
+    pub(crate) fn new(distribution: &Poisson<3>, mesh: &'a Mesh, spacing: Spacing) -> Self {
+        let radius = distribution.radius;
+
+        let rng = distribution.rng();
+
+        let mut total = 0.0;
+        let cumulative_area: Vec<Float> = (0..mesh.triangles.len())
+            .map(|tri| {
+                total += mesh.area(tri);
+                total
+            })
+            .collect();
+
+        // Throw `num_samples` darts for each point that a densely-packed surface could hold.
+        // Without a positive radius there's no end to the points we could fit, so throw none.
+        let capacity = if radius > 0.0 {
+            (total / (radius.powi(2) * (3.0 as Float).sqrt() / 2.0))
+                .ceil()
+                .max(1.0) as usize
+        } else {
+            1
+        };
+        let remaining = if total > 0.0 && radius > 0.0 {
+            (distribution.num_samples as usize).saturating_mul(capacity)
+        } else {
+            0
+        };
+
+        // We maintain a grid of our samples for faster radius checking
+        let grid = Buckets::new(mesh.bounds(), radius, false, capacity);
+
+        let edges = match spacing {
+            Spacing::Euclidean => Vec::new(),
+            Spacing::Geodesic => mesh.edges(),
+        };
+
+        SurfaceIter {
+            mesh,
+            spacing,
+            radius,
+            rng,
+            grid,
+            points: Vec::new(),
+            cumulative_area,
+            edges,
+            remaining,
+        }
+    }
+
+    /// Generate a random point on the surface, with every part of the surface equally likely
+    fn generate_random_point(&mut self) -> SurfacePoint {
+        let total = self.cumulative_area.last().copied().unwrap_or(0.0);
+        let target = self.rng.gen::<Float>() * total;
+        let triangle = partition_point(&self.cumulative_area, |&area| area <= target)
+            .min(self.cumulative_area.len() - 1);
+
+        // Folding the unit square in half gives uniformly distributed barycentric coordinates
+        let (mut u, mut v) = (self.rng.gen::<Float>(), self.rng.gen::<Float>());
+        if u + v > 1.0 {
+            u = 1.0 - u;
+            v = 1.0 - v;
+        }
+        let barycentric = [1.0 - u - v, u, v];
+
+        let corners = self.mesh.corners(triangle);
+        let mut point = [0.0; 3];
+        for (corner, weight) in corners.iter().zip(barycentric.iter()) {
+            for i in 0..3 {
+                point[i] += corner[i] * weight;
+            }
+        }
+
+        SurfacePoint {
+            point,
+            triangle,
+            barycentric,
+        }
+    }
+
+    /// Returns true if there is at least one other sample point within `radius` of this point
+    fn in_neighborhood(&self, sample: &SurfacePoint) -> bool {
+        let r_squared = self.radius.powi(2);
+
+        let mut found = false;
+        self.grid.for_each_near(sample.point, self.radius, |idx| {
+            let other = &self.points[idx];
+            if found || self.grid.distance_squared(sample.point, other.point) >= r_squared {
+                return;
+            }
+
+            // The geodesic distance is never shorter than the Euclidean one, so we only need to
+            // look at it for points that are too close through space
+            found = match self.spacing {
+                Spacing::Euclidean => true,
+                Spacing::Geodesic => self.geodesic_distance(sample, other) < self.radius,
+            };
+        });
+
+        found
+    }
+
+    /// Approximate the distance along the surface between two points
+    ///
+    /// Points on the same or neighboring triangles use the straight-line distance; otherwise we
+    /// search along the mesh's edges, giving up once the path grows longer than `radius`.
+    fn geodesic_distance(&self, a: &SurfacePoint, b: &SurfacePoint) -> Float {
+        let tri_a = self.mesh.triangles[a.triangle];
+        let tri_b = self.mesh.triangles[b.triangle];
+        if tri_a.iter().any(|v| tri_b.contains(v)) {
+            return distance(a.point, b.point);
+        }
+
+        // Dijkstra's algorithm from the corners of `a`'s triangle to those of `b`'s
+        let mut best: HashMap<usize, Float> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        for &v in &tri_a {
+            let dist = distance(a.point, self.mesh.vertices[v]);
+            best.insert(v, dist);
+            queue.push(Reverse(Keyed(dist, v)));
+        }
+
+        let mut shortest = Float::INFINITY;
+        while let Some(Reverse(Keyed(dist, v))) = queue.pop() {
+            if dist >= self.radius.min(shortest) {
+                break;
+            }
+            if matches!(best.get(&v), Some(&d) if d < dist) {
+                continue;
+            }
+            if tri_b.contains(&v) {
+                shortest = shortest.min(dist + distance(self.mesh.vertices[v], b.point));
+            }
+
+            for &(next, length) in &self.edges[v] {
+                let next_dist = dist + length;
+                if !matches!(best.get(&next), Some(&d) if d <= next_dist) {
+                    best.insert(next, next_dist);
+                    queue.push(Reverse(Keyed(next_dist, next)));
+                }
+            }
+        }
+
+        shortest
+    }
+
+    /// Add a point to our pattern
+    fn add_point(&mut self, sample: SurfacePoint) {
+        self.grid.insert(self.points.len(), sample.point);
+        self.points.push(sample);
+    }
+}
+
+impl<'a> Iterator for SurfaceIter<'a> {
+    type Item = SurfacePoint;
+
+    fn next(&mut self) -> Option<SurfacePoint> {
+        while self.remaining > 0 {
+            self.remaining -= 1;
+
+            let sample = self.generate_random_point();
+            if !self.in_neighborhood(&sample) {
+                self.add_point(sample);
+
+                return Some(sample);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> FusedIterator for SurfaceIter<'a> {}
+
+/// How to decide whether a point lies inside a closed [`Mesh`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Containment {
+    /// Count how many times a ray from the point crosses the surface; an odd count is inside
+    ///
+    /// This is fast, but the mesh must be watertight.
+    RayParity,
+    /// Measure how many times the surface winds around the point, using the solid angle of
+    /// every triangle
+    ///
+    /// This takes time proportional to the number of triangles for every test, but tolerates
+    /// small holes and overlapping parts in the mesh.
+    WindingNumber,
+}
+
+/// A closed mesh, prepared for testing whether points lie inside it
+pub(crate) struct Volume {
+    /// The surface of the volume
+    mesh: Mesh,
+    /// How to test points against the surface
+    containment: Containment,
+    /// The lowest corner of the mesh in the XY plane
+    origin: [Float; 2],
+    /// The size of each column of the grid in the XY plane
+    cell_size: [Float; 2],
+    /// The number of columns along each axis of the grid
+    cells: [usize; 2],
+    /// The triangles overlapping each column of the grid, so a ray cast along Z only needs to
+    /// test a handful of them
+    columns: Vec<Vec<usize>>,
+}
+
+impl Volume {
+    /// Prepare a mesh for testing points against it
+    pub(crate) fn new(mesh: Mesh, containment: Containment) -> Self {
+        let (origin, size) = mesh.bounds();
+        let origin = [origin[0], origin[1]];
+
+        // Aim for around one triangle per column
+        let per_axis = ((mesh.triangles.len() as Float).sqrt().ceil() as usize).clamp(1, 1024);
+        let cells = [per_axis; 2];
+        let cell_size = [
+            (size[0] / per_axis as Float).max(Float::MIN_POSITIVE),
+            (size[1] / per_axis as Float).max(Float::MIN_POSITIVE),
+        ];
+
+        let mut volume = Volume {
+            mesh,
+            containment,
+            origin,
+            cell_size,
+            cells,
+            columns: vec![Vec::new(); cells[0] * cells[1]],
+        };
+
+        if containment == Containment::RayParity {
+            for tri in 0..volume.mesh.triangles.len() {
+                let corners = volume.mesh.corners(tri);
+                let lo = volume.column(
+                    corners[0][0].min(corners[1][0]).min(corners[2][0]),
+                    corners[0][1].min(corners[1][1]).min(corners[2][1]),
+                );
+                let hi = volume.column(
+                    corners[0][0].max(corners[1][0]).max(corners[2][0]),
+                    corners[0][1].max(corners[1][1]).max(corners[2][1]),
+                );
+
+                for x in lo[0]..=hi[0] {
+                    for y in lo[1]..=hi[1] {
+                        volume.columns[x * cells[1] + y].push(tri);
+                    }
+                }
+            }
+        }
+
+        volume
+    }
+
+    /// The column of the grid holding the given X and Y coordinates, clamped to the grid
+    fn column(&self, x: Float, y: Float) -> [usize; 2] {
+        let cx = ((x - self.origin[0]) / self.cell_size[0]).floor().max(0.0) as usize;
+        let cy = ((y - self.origin[1]) / self.cell_size[1]).floor().max(0.0) as usize;
+
+        [cx.min(self.cells[0] - 1), cy.min(self.cells[1] - 1)]
+    }
+
+    /// Returns true if the point lies inside the mesh
+    pub(crate) fn contains(&self, point: Point<3>) -> bool {
+        match self.containment {
+            Containment::RayParity => self.ray_parity(point),
+            Containment::WindingNumber => self.winding_number(point).abs() >= 0.5,
+        }
+    }
+
+    /// Count the crossings of a ray cast from the point along +Z; odd means inside
+    fn ray_parity(&self, point: Point<3>) -> bool {
+        let [cx, cy] = self.column(point[0], point[1]);
+        let mut inside = false;
+
+        for &tri in &self.columns[cx * self.cells[1] + cy] {
+            let [a, mut b, mut c] = self.mesh.corners(tri);
+
+            // Work with the triangle wound counter-clockwise in the XY plane
+            let area = edge_function(a, b, c);
+            if area == 0.0 {
+                // Seen edge-on, the ray can't cross this triangle
+                continue;
+            }
+            if area < 0.0 {
+                std::mem::swap(&mut b, &mut c);
+            }
+
+            // When the ray passes exactly through an edge or vertex, only the triangles for which
+            // that edge is a "top" or "left" edge count it, so shared edges are counted just once
+            let weights = [
+                edge_function(b, c, point),
+                edge_function(c, a, point),
+                edge_function(a, b, point),
+            ];
+            let edges = [(b, c), (c, a), (a, b)];
+            let covers = weights
+                .iter()
+                .zip(edges.iter())
+                .all(|(&w, &(from, to))| w > 0.0 || (w == 0.0 && is_top_left(from, to)));
+
+            if covers {
+                let area = area.abs();
+                let z = (weights[0] * a[2] + weights[1] * b[2] + weights[2] * c[2]) / area;
+                if z > point[2] {
+                    inside = !inside;
+                }
+            }
+        }
+
+        inside
+    }
+
+    /// The generalized winding number of the surface around the point
+    ///
+    /// This is the sum of the signed solid angles of every triangle as seen from the point, in
+    /// whole turns, using the formula of Van Oosterom and Strackee.
+    fn winding_number(&self, point: Point<3>) -> Float {
+        let mut total = 0.0;
+
+        for tri in 0..self.mesh.triangles.len() {
+            let [a, b, c] = self.mesh.corners(tri);
+            let (a, b, c) = (sub(a, point), sub(b, point), sub(c, point));
+            let (la, lb, lc) = (norm(a), norm(b), norm(c));
+
+            let numerator = dot(a, cross(b, c));
+            let denominator = la * lb * lc + dot(a, b) * lc + dot(b, c) * la + dot(c, a) * lb;
+            total += 2.0 * numerator.atan2(denominator);
+        }
+
+        total / (4.0 * std::f64::consts::PI as Float)
+    }
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `point` in the XY plane
+///
+/// This is positive if `point` lies to the left of the edge from `a` to `b`.
+fn edge_function(a: Point<3>, b: Point<3>, point: Point<3>) -> Float {
+    (b[0] - a[0]) * (point[1] - a[1]) - (b[1] - a[1]) * (point[0] - a[0])
+}
+
+/// Returns true for the edges of a counter-clockwise triangle that own points lying exactly on
+/// them; the same edge of the neighboring triangle runs the other way and doesn't
+fn is_top_left(from: Point<3>, to: Point<3>) -> bool {
+    let (dx, dy) = (to[0] - from[0], to[1] - from[1]);
+
+    dy > 0.0 || (dy == 0.0 && dx < 0.0)
+}
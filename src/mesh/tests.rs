@@ -0,0 +1,306 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+use crate::tests::assert_spaced;
+use crate::Poisson3D;
+
+/// An axis-aligned unit cube, with outward-facing triangles
+fn cube() -> Mesh {
+    let vertices = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [1.0, 0.0, 1.0],
+        [1.0, 1.0, 1.0],
+        [0.0, 1.0, 1.0],
+    ];
+    let indices = [
+        0, 2, 1, 0, 3, 2, // bottom
+        4, 5, 6, 4, 6, 7, // top
+        0, 1, 5, 0, 5, 4, // front
+        2, 3, 7, 2, 7, 6, // back
+        0, 4, 7, 0, 7, 3, // left
+        1, 2, 6, 1, 6, 5, // right
+    ];
+
+    Mesh::new(&vertices, &indices)
+}
+
+/// Two parallel unit squares, `gap` apart and not connected to each other
+fn sheets(gap: Float) -> Mesh {
+    let vertices = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, gap],
+        [1.0, 0.0, gap],
+        [1.0, 1.0, gap],
+        [0.0, 1.0, gap],
+    ];
+    let indices = [0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7];
+
+    Mesh::new(&vertices, &indices)
+}
+
+#[test]
+fn new_mesh() {
+    let mesh = cube();
+
+    assert_eq!(mesh.vertices().len(), 8);
+    assert_eq!(mesh.triangles().len(), 12);
+    assert_eq!(mesh.triangles()[1], [0, 3, 2]);
+    assert!((mesh.area(0) - 0.5).abs() < 1e-6);
+}
+
+#[test]
+#[should_panic]
+fn partial_triangle_panics() {
+    Mesh::new(&[[0.0; 3]; 3], &[0, 1]);
+}
+
+#[test]
+#[should_panic]
+fn bad_index_panics() {
+    Mesh::new(&[[0.0; 3]; 3], &[0, 1, 3]);
+}
+
+#[test]
+fn points_lie_on_their_triangle() {
+    let mesh = cube();
+    let mut poisson = Poisson3D::new();
+    poisson.with_radius(0.1).with_seed(1337);
+
+    for sample in poisson.iter_surface(&mesh, Spacing::Euclidean) {
+        let corners = mesh.corners(sample.triangle);
+
+        assert!(sample.barycentric.iter().all(|&w| w >= 0.0));
+        assert!((sample.barycentric.iter().sum::<Float>() - 1.0).abs() < 1e-6);
+        for (i, &coord) in sample.point.iter().enumerate() {
+            let expected = corners
+                .iter()
+                .zip(sample.barycentric.iter())
+                .map(|(corner, weight)| corner[i] * weight)
+                .sum::<Float>();
+            assert!((coord - expected).abs() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn surface_points_are_spaced() {
+    let mesh = cube();
+    let mut poisson = Poisson3D::new();
+    poisson.with_radius(0.1).with_seed(1337);
+    let points: Vec<_> = poisson.iter_surface(&mesh, Spacing::Euclidean).collect();
+
+    // A well-filled surface of area 6 holds a few hundred points at this radius
+    assert!(points.len() > 300, "only {} points", points.len());
+    let points: Vec<_> = points.iter().map(|p| p.point).collect();
+    assert_spaced(&points, 0.1, None);
+}
+
+#[test]
+fn surface_is_covered_evenly() {
+    let mesh = cube();
+    let mut poisson = Poisson3D::new();
+    poisson.with_radius(0.1).with_seed(42);
+    let points: Vec<_> = poisson.iter_surface(&mesh, Spacing::Euclidean).collect();
+
+    // Each face of the cube should get roughly a sixth of the points
+    for face in 0..6 {
+        let count = points.iter().filter(|p| p.triangle / 2 == face).count() as Float;
+        let share = count / points.len() as Float;
+        assert!(
+            (share - 1.0 / 6.0).abs() < 0.03,
+            "face {} has {}",
+            face,
+            share
+        );
+    }
+}
+
+#[test]
+fn geodesic_spacing_allows_points_through_thin_walls() {
+    let mesh = sheets(0.05);
+    let mut poisson = Poisson3D::new();
+    poisson.with_radius(0.2).with_seed(7);
+
+    let euclidean: Vec<_> = poisson.iter_surface(&mesh, Spacing::Euclidean).collect();
+    let geodesic: Vec<_> = poisson.iter_surface(&mesh, Spacing::Geodesic).collect();
+
+    // Points on opposite sheets block each other through space, but not along the surface
+    assert!(geodesic.len() > euclidean.len() * 3 / 2);
+    for (i, a) in geodesic.iter().enumerate() {
+        for b in &geodesic[i + 1..] {
+            if a.triangle / 2 == b.triangle / 2 {
+                assert!(distance(a.point, b.point) >= 0.2);
+            }
+        }
+    }
+}
+
+#[test]
+fn geodesic_distance_follows_edges() {
+    // A strip folded in half: two squares meeting at the edge x = 1
+    let vertices = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.1],
+        [0.0, 1.0, 0.1],
+    ];
+    let indices = [0, 1, 2, 0, 2, 3, 1, 4, 5, 1, 5, 2];
+    let mesh = Mesh::new(&vertices, &indices);
+    let mut poisson = Poisson3D::new();
+    poisson.with_radius(5.0);
+    let iter = poisson.iter_surface(&mesh, Spacing::Geodesic);
+
+    let a = SurfacePoint {
+        point: [0.0, 0.0, 0.0],
+        triangle: 0,
+        barycentric: [1.0, 0.0, 0.0],
+    };
+    let b = SurfacePoint {
+        point: [0.0, 0.0, 0.1],
+        triangle: 2,
+        barycentric: [0.0, 1.0, 0.0],
+    };
+
+    // Triangles 0 and 2 share vertex 1, so they're treated as neighbors
+    assert!((iter.geodesic_distance(&a, &b) - 0.1).abs() < 1e-6);
+
+    // Points on disconnected parts of a mesh can't reach each other at all
+    let mesh = sheets(0.1);
+    let iter = poisson.iter_surface(&mesh, Spacing::Geodesic);
+    let c = SurfacePoint {
+        point: [0.0, 0.0, 0.0],
+        triangle: 0,
+        barycentric: [1.0, 0.0, 0.0],
+    };
+    let d = SurfacePoint {
+        point: [0.0, 0.0, 0.1],
+        triangle: 2,
+        barycentric: [1.0, 0.0, 0.0],
+    };
+    assert!(iter.geodesic_distance(&c, &d).is_infinite());
+}
+
+#[test]
+fn empty_mesh_has_no_points() {
+    let mesh = Mesh::new(&[], &[]);
+    let poisson = Poisson3D::new();
+
+    assert_eq!(poisson.iter_surface(&mesh, Spacing::Euclidean).count(), 0);
+}
+
+#[test]
+fn zero_radius_yields_nothing() {
+    let mesh = cube();
+    let mut poisson = Poisson3D::new();
+    poisson.with_radius(0.0);
+
+    for &spacing in &[Spacing::Euclidean, Spacing::Geodesic] {
+        assert_eq!(poisson.iter_surface(&mesh, spacing).count(), 0);
+    }
+}
+
+/// A regular tetrahedron, matching `tetrahedron_shape`
+fn tetrahedron() -> Mesh {
+    let vertices = [
+        [1.0, 1.0, 1.0],
+        [1.0, -1.0, -1.0],
+        [-1.0, 1.0, -1.0],
+        [-1.0, -1.0, 1.0],
+    ];
+    let indices = [0, 1, 2, 0, 3, 1, 0, 2, 3, 1, 3, 2];
+
+    Mesh::new(&vertices, &indices)
+}
+
+fn tetrahedron_shape() -> crate::Shape<3> {
+    crate::Shape::Simplex {
+        vertices: tetrahedron().vertices().to_vec(),
+    }
+}
+
+#[test]
+fn mesh_bounds() {
+    assert_eq!(cube().bounds(), ([0.0; 3], [1.0; 3]));
+    assert_eq!(tetrahedron().bounds(), ([-1.0; 3], [2.0; 3]));
+    assert_eq!(Mesh::new(&[], &[]).bounds(), ([0.0; 3], [0.0; 3]));
+}
+
+#[test]
+fn containment_matches_shape() {
+    let shape = tetrahedron_shape();
+    let parity = Volume::new(tetrahedron(), Containment::RayParity);
+    let winding = Volume::new(tetrahedron(), Containment::WindingNumber);
+    let mut rng = Rand::seed_from_u64(1337);
+
+    for _ in 0..2000 {
+        let mut point = [0.0; 3];
+        for i in point.iter_mut() {
+            *i = rng.gen::<Float>() * 2.4 - 1.2;
+        }
+
+        assert_eq!(parity.contains(point), shape.contains(point), "{:?}", point);
+        assert_eq!(
+            winding.contains(point),
+            shape.contains(point),
+            "{:?}",
+            point
+        );
+    }
+}
+
+#[test]
+fn ray_through_shared_edge_counts_once() {
+    let volume = Volume::new(cube(), Containment::RayParity);
+
+    // These rays run exactly along the diagonal edges splitting each face of the cube
+    assert!(volume.contains([0.5, 0.5, 0.5]));
+    assert!(volume.contains([0.25, 0.25, 0.5]));
+    assert!(!volume.contains([0.5, 0.5, 1.5]));
+    assert!(!volume.contains([0.5, 0.5, -0.5]));
+}
+
+#[test]
+fn winding_ignores_orientation() {
+    let mut inverted = cube();
+    for tri in inverted.triangles.iter_mut() {
+        tri.swap(1, 2);
+    }
+    let volume = Volume::new(inverted, Containment::WindingNumber);
+
+    assert!(volume.contains([0.5, 0.5, 0.5]));
+    assert!(!volume.contains([1.5, 0.5, 0.5]));
+}
+
+#[test]
+fn volume_points_fill_mesh() {
+    let mesh = tetrahedron();
+    let shape = tetrahedron_shape();
+
+    for &containment in &[Containment::RayParity, Containment::WindingNumber] {
+        let mut poisson = Poisson3D::new();
+        poisson.with_radius(0.2).with_seed(1337);
+        let points: Vec<_> = poisson.iter_volume(&mesh, containment).collect();
+
+        // The same distribution in the equivalent shape should have a similar number of points
+        poisson.with_shape(shape.clone(), 0.2);
+        let expected = poisson.iter().count() as Float;
+
+        assert!((points.len() as Float - expected).abs() < expected * 0.1);
+        assert!(points.iter().all(|&p| shape.contains(p)));
+        assert_spaced(&points, 0.2, None);
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Point, Poisson, SpatialIndex};
+#[cfg(feature = "derive_serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+/// Which distance to the feature points [`Worley`] noise measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "derive_serde", derive(Serialize, Deserialize))]
+pub enum Distance {
+    /// The distance to the nearest point, which gives rounded cells that are darkest at their
+    /// centers
+    F1,
+    /// The distance to the second-nearest point
+    F2,
+    /// The difference between the two, which is zero along the cells' edges and so draws their
+    /// outlines
+    F2MinusF1,
+}
+
+/// Worley (cellular) noise, using the points of a Poisson disk distribution as its feature points
+///
+/// Because the feature points are evenly spaced, the cells are all much the same size, without
+/// the clumps and voids of noise built on a jittered grid. Queries use the distribution's own grid
+/// to find the nearest points, and in a periodic box distances wrap around, so the noise tiles
+/// seamlessly.
+///
+/// Distances are in the units of the distribution's space: F1 is rarely more than `2 * radius`.
+///
+/// Build one with [`Poisson::worley`].
+///
+/// ```
+/// # use fast_poisson::{Distance, Poisson2D};
+/// let mut poisson = Poisson2D::new();
+/// poisson.with_periodic(true);
+/// let noise = poisson.worley();
+///
+/// let value = noise.sample([0.25, 0.5], Distance::F1);
+/// let [f1, f2] = noise.distances([0.25, 0.5]);
+///
+/// // A 256×256 texture that tiles seamlessly
+/// let texture = noise.image(256, 256, Distance::F2MinusF1);
+/// ```
+pub struct Worley<const N: usize> {
+    /// The feature points
+    index: SpatialIndex<N>,
+    /// The lowest corner and the size of the distribution's bounding box
+    bounds: (Point<N>, [Float; N]),
+}
+
+impl<const N: usize> Worley<N> {
+    /// Generate the feature points
+    pub(crate) fn new(distribution: &Poisson<N>) -> Self {
+        Worley {
+            index: distribution.index(),
+            bounds: distribution.bounds(),
+        }
+    }
+
+    /// The feature points the noise is built on
+    pub fn index(&self) -> &SpatialIndex<N> {
+        &self.index
+    }
+
+    /// The distances from the given position to the nearest and second-nearest feature points
+    ///
+    /// If there aren't enough feature points, the missing distances are infinite.
+    pub fn distances(&self, point: Point<N>) -> [Float; 2] {
+        let mut distances = [Float::INFINITY; 2];
+        for (distance, (d_squared, _)) in distances
+            .iter_mut()
+            .zip(self.index.k_nearest_by_distance(point, 2))
+        {
+            *distance = d_squared.sqrt();
+        }
+
+        distances
+    }
+
+    /// The value of the noise at the given position
+    pub fn sample(&self, point: Point<N>, distance: Distance) -> Float {
+        let [f1, f2] = self.distances(point);
+
+        match distance {
+            Distance::F1 => f1,
+            Distance::F2 => f2,
+            Distance::F2MinusF1 => f2 - f1,
+        }
+    }
+}
+
+impl Worley<2> {
+    /// Sample the noise into an image covering the distribution's bounding box
+    ///
+    /// The image is `width` pixels across and `height` pixels down, in rows starting from the
+    /// lowest corner of the box; each pixel holds the noise at its center.
+    pub fn image(&self, width: usize, height: usize, distance: Distance) -> Vec<Float> {
+        let (origin, size) = self.bounds;
+        let mut pixels = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            let py = origin[1] + (y as Float + 0.5) / height as Float * size[1];
+            for x in 0..width {
+                let px = origin[0] + (x as Float + 0.5) / width as Float * size[0];
+                pixels.push(self.sample([px, py], distance));
+            }
+        }
+
+        pixels
+    }
+}
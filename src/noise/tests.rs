@@ -0,0 +1,101 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+use crate::grid::distance_squared;
+use crate::{Poisson2D, Poisson3D};
+
+/// The distances to the two nearest points, found the slow way
+fn brute_force<const N: usize>(
+    points: &[Point<N>],
+    query: Point<N>,
+    period: Option<[Float; N]>,
+) -> [Float; 2] {
+    let mut distances: Vec<Float> = points
+        .iter()
+        .map(|&p| distance_squared(query, p, period).sqrt())
+        .collect();
+    distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    [distances[0], distances[1]]
+}
+
+#[test]
+fn distances_match_brute_force() {
+    let mut poisson = Poisson3D::new();
+    poisson.with_dimensions([1.0, 1.0, 1.0], 0.2).with_seed(3);
+    let noise = poisson.worley();
+    let points: Vec<_> = noise.index().points().collect();
+
+    for &query in [
+        [0.5, 0.5, 0.5],
+        [0.0, 0.0, 0.0],
+        [0.9, 0.1, 0.33],
+        [2.0, 0.5, -1.0],
+    ]
+    .iter()
+    {
+        let [f1, f2] = noise.distances(query);
+        let expected = brute_force(&points, query, None);
+        assert!((f1 - expected[0]).abs() < 1e-6);
+        assert!((f2 - expected[1]).abs() < 1e-6);
+
+        assert_eq!(noise.sample(query, Distance::F1), f1);
+        assert_eq!(noise.sample(query, Distance::F2), f2);
+        assert_eq!(noise.sample(query, Distance::F2MinusF1), f2 - f1);
+    }
+}
+
+#[test]
+fn periodic_noise_tiles() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_periodic(true).with_seed(12);
+    let noise = poisson.worley();
+    let points: Vec<_> = noise.index().points().collect();
+
+    for &query in [[0.0, 0.5], [0.999, 0.001], [0.3, 0.0]].iter() {
+        let [f1, f2] = noise.distances(query);
+        let expected = brute_force(&points, query, Some([1.0, 1.0]));
+        assert!((f1 - expected[0]).abs() < 1e-6);
+        assert!((f2 - expected[1]).abs() < 1e-6);
+    }
+
+    // Opposite edges of the image meet smoothly, as they're neighbors across the seam
+    let image = noise.image(64, 64, Distance::F1);
+    let step = 1.0 / 64.0;
+    for row in 0..64 {
+        let (left, right) = (image[row * 64], image[row * 64 + 63]);
+        assert!((left - right).abs() <= step * 1.001);
+    }
+}
+
+#[test]
+fn image_layout() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([2.0, 1.0], 0.1).with_seed(8);
+    let noise = poisson.worley();
+
+    let image = noise.image(8, 4, Distance::F2MinusF1);
+    assert_eq!(image.len(), 32);
+    // Row 1, column 3 is centered on (0.875, 0.375)
+    assert_eq!(
+        image[8 + 3],
+        noise.sample([0.875, 0.375], Distance::F2MinusF1)
+    );
+    assert!(image.iter().all(|&v| v >= 0.0));
+}
+
+#[test]
+fn too_few_points() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([0.1, 0.1], 1.0).with_seed(1);
+    let noise = poisson.worley();
+
+    let [f1, f2] = noise.distances([0.05, 0.05]);
+    assert!(f1.is_finite());
+    assert!(f2.is_infinite());
+}
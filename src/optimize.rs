@@ -0,0 +1,454 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Post-processing that moves the points of a finished distribution: farthest-point optimization,
+//! after Schlömer, Heck and Deussen's ["Farthest-Point Optimized Point Sets with Maximized Minimum
+//! Distance"][FPO], and Lloyd's relaxation toward a centroidal Voronoi tessellation
+//!
+//! [FPO]: https://doi.org/10.1145/2018323.2018345
+
+use super::{Float, Point, Poisson};
+use crate::elimination::Space;
+use crate::grid::{wrap, Buckets};
+use crate::iter::{random_point_in, Rand};
+use rand::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+/// How many random candidates to try near each point's current position
+const LOCAL_CANDIDATES: usize = 16;
+/// How many random candidates to try anywhere in the space, to find larger holes
+const GLOBAL_CANDIDATES: usize = 4;
+/// How many times to try stepping a point further from its nearest neighbor
+const ASCENT_STEPS: usize = 16;
+/// The relative improvement in the mean spacing below which we consider the points converged
+const TOLERANCE: Float = 1e-4;
+/// How many random steps to take when searching for the center of a hole
+const REFINE_STEPS: usize = 64;
+/// How many random samples to spread over each point's Voronoi cell when relaxing
+const SAMPLES_PER_POINT: usize = 64;
+/// The seed for the search for holes, so the same points always give the same coverage radius
+const COVERAGE_SEED: u64 = 0;
+
+/// How far an optimization got
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Convergence {
+    /// The number of passes over the points that were made
+    pub iterations: usize,
+    /// The smallest distance between any two points
+    pub min_distance: Float,
+    /// The average distance from each point to its nearest neighbor
+    pub mean_distance: Float,
+    /// Whether the points settled before the iterations ran out
+    pub converged: bool,
+}
+
+/// Moves points around to spread them out as evenly as possible
+struct Optimizer<'a, const N: usize> {
+    /// The distribution whose space the points fill
+    distribution: &'a Poisson<N>,
+    /// The RNG
+    rng: Rand,
+    /// The grid of buckets holding the points
+    grid: Buckets<N>,
+    /// The distance we expect between neighboring points
+    spacing: Float,
+}
+
+impl<'a, const N: usize> Optimizer<'a, N> {
+    /// Distance from the point to the nearest of the points in the grid, other than `skip`
+    fn nearest(
+        &self,
+        points: &[Point<N>],
+        point: Point<N>,
+        skip: Option<usize>,
+    ) -> (Float, Option<usize>) {
+        let (d_squared, idx) = self.nearest_squared(points, point, skip);
+
+        (d_squared.sqrt(), idx)
+    }
+
+    /// Like [`Optimizer::nearest`], with the squared distance
+    fn nearest_squared(
+        &self,
+        points: &[Point<N>],
+        point: Point<N>,
+        skip: Option<usize>,
+    ) -> (Float, Option<usize>) {
+        let (_, size) = self.distribution.bounds();
+        let diagonal = size.iter().map(|d| d.powi(2)).sum::<Float>().sqrt();
+
+        // Look further and further out until we find a neighbor. A space with no volume has no
+        // spacing, so start from a radius that can actually grow.
+        let mut radius = self
+            .spacing
+            .max(diagonal / points.len().max(1) as Float)
+            .max(Float::MIN_POSITIVE);
+        loop {
+            let mut best = (Float::INFINITY, None);
+            self.grid.for_each_near(point, radius, |idx| {
+                let dist = self.grid.distance_squared(point, points[idx]);
+                if dist < best.0 && Some(idx) != skip {
+                    best = (dist, Some(idx));
+                }
+            });
+
+            if best.0 <= radius.powi(2) || radius > diagonal {
+                return best;
+            }
+            radius *= 2.0;
+        }
+    }
+
+    /// Pull a point that has strayed outside the bounding box back onto its edge
+    ///
+    /// The highest edges themselves are outside the box, so it stops just short of them.
+    fn clamp(&self, mut point: Point<N>) -> Point<N> {
+        let (origin, size) = self.distribution.bounds();
+        for i in 0..N {
+            let highest = origin[i] + size[i] * (1.0 - Float::EPSILON);
+            point[i] = point[i].max(origin[i]).min(highest);
+        }
+
+        point
+    }
+
+    /// A random point anywhere in the space, if we can find one
+    fn random_point(&mut self) -> Option<Point<N>> {
+        let bounds = self.distribution.bounds();
+
+        for _ in 0..100 {
+            let point = random_point_in(&mut self.rng, bounds);
+            if self.distribution.contains(point) {
+                return Some(point);
+            }
+        }
+
+        None
+    }
+
+    /// A random point within `radius` of the given one
+    fn random_point_near(&mut self, around: Point<N>, radius: Float) -> Point<N> {
+        let mut point = around;
+        for p in point.iter_mut() {
+            *p += (self.rng.gen::<Float>() * 2.0 - 1.0) * radius;
+        }
+
+        wrap(point, self.distribution.period())
+    }
+
+    /// Move the point at `idx` to the position furthest from all of the others that we can find
+    fn relocate(&mut self, points: &mut [Point<N>], idx: usize) {
+        self.grid.remove(idx, points[idx]);
+
+        let mut best = points[idx];
+        let (mut best_dist, _) = self.nearest(points, best, None);
+
+        // Look for a better spot near where the point is, and a few anywhere at all
+        for n in 0..LOCAL_CANDIDATES + GLOBAL_CANDIDATES {
+            let candidate = if n < LOCAL_CANDIDATES {
+                Some(self.random_point_near(points[idx], best_dist.min(self.spacing) * 1.5))
+            } else {
+                self.random_point()
+            };
+
+            if let Some(candidate) = candidate.filter(|&p| self.distribution.contains(p)) {
+                let (dist, _) = self.nearest(points, candidate, None);
+                if dist > best_dist {
+                    best = candidate;
+                    best_dist = dist;
+                }
+            }
+        }
+
+        // Then climb away from the nearest neighbor for as long as that helps
+        let (best, _) = self.climb(points, best, best_dist);
+
+        points[idx] = best;
+        self.grid.insert(idx, best);
+    }
+
+    /// Step from `best`, `best_dist` away from its nearest point, further from that point for as
+    /// long as that helps; returns where it ends up and how far that is from the nearest point
+    fn climb(
+        &self,
+        points: &[Point<N>],
+        mut best: Point<N>,
+        mut best_dist: Float,
+    ) -> (Point<N>, Float) {
+        let mut step = best_dist.min(self.spacing) / 2.0;
+        for _ in 0..ASCENT_STEPS {
+            let neighbor = match self.nearest(points, best, None) {
+                (_, Some(neighbor)) => points[neighbor],
+                (_, None) => break,
+            };
+
+            // The direction away from the neighbor, the short way around a periodic box
+            let mut away = [0.0; N];
+            for (i, a) in away.iter_mut().enumerate() {
+                *a = best[i] - neighbor[i];
+                if let Some(period) = self.distribution.period() {
+                    *a -= period[i] * (*a / period[i]).round();
+                }
+            }
+            let length = away.iter().map(|a| a.powi(2)).sum::<Float>().sqrt();
+            if length == 0.0 {
+                break;
+            }
+
+            let mut candidate = best;
+            for (c, a) in candidate.iter_mut().zip(away.iter()) {
+                *c += a / length * step;
+            }
+            let candidate = wrap(candidate, self.distribution.period());
+
+            let dist = if self.distribution.contains(candidate) {
+                self.nearest(points, candidate, None).0
+            } else {
+                0.0
+            };
+            if dist > best_dist {
+                best = candidate;
+                best_dist = dist;
+            } else {
+                step /= 2.0;
+            }
+        }
+
+        (best, best_dist)
+    }
+
+    /// The smallest and the mean distance from each point to its nearest neighbor
+    fn spacing_of(&self, points: &[Point<N>]) -> (Float, Float) {
+        let distances: Vec<Float> = (0..points.len())
+            .map(|idx| self.nearest(points, points[idx], Some(idx)).0)
+            .collect();
+
+        let min = distances.iter().copied().fold(Float::INFINITY, Float::min);
+        let mean = distances.iter().sum::<Float>() / points.len().max(1) as Float;
+
+        (min, mean)
+    }
+}
+
+impl<'a, const N: usize> Optimizer<'a, N> {
+    /// Prepare to move the points around the distribution's space
+    fn new(distribution: &'a Poisson<N>, points: &[Point<N>]) -> Self {
+        let rng = distribution.rng();
+
+        let space = Space::of(distribution);
+        let spacing = 2.0 * space.max_radius(points.len());
+        let mut grid = Buckets::new(space.bounds, spacing, space.period.is_some(), points.len());
+        for (idx, &point) in points.iter().enumerate() {
+            grid.insert(idx, point);
+        }
+
+        Optimizer {
+            distribution,
+            rng,
+            grid,
+            spacing,
+        }
+    }
+}
+
+/// Move the points so that they're spread out as evenly as possible
+pub(crate) fn optimize<const N: usize>(
+    distribution: &Poisson<N>,
+    points: &mut [Point<N>],
+    iterations: usize,
+) -> Convergence {
+    let mut optimizer = Optimizer::new(distribution, points);
+
+    let (mut min_distance, mut mean_distance) = optimizer.spacing_of(points);
+    for iteration in 0..iterations {
+        for idx in 0..points.len() {
+            optimizer.relocate(points, idx);
+        }
+
+        let (min, mean) = optimizer.spacing_of(points);
+        let improvement = (mean - mean_distance) / mean_distance;
+        min_distance = min;
+        mean_distance = mean;
+
+        if improvement.is_nan() || improvement <= TOLERANCE {
+            return Convergence {
+                iterations: iteration + 1,
+                min_distance,
+                mean_distance,
+                converged: true,
+            };
+        }
+    }
+
+    Convergence {
+        iterations,
+        min_distance,
+        mean_distance,
+        converged: false,
+    }
+}
+
+/// Move each point to the centroid of its Voronoi cell, until they move less than `tolerance`
+pub(crate) fn relax<const N: usize>(
+    distribution: &Poisson<N>,
+    points: &mut [Point<N>],
+    iterations: usize,
+    tolerance: Float,
+) -> Convergence {
+    let mut optimizer = Optimizer::new(distribution, points);
+    let period = distribution.period();
+
+    for iteration in 0..iterations {
+        // Estimate each cell's centroid from random samples, each belonging to its nearest point;
+        // offsets are measured from the point, so cells can wrap around a periodic box
+        let mut sums = vec![[0.0; N]; points.len()];
+        let mut counts = vec![0_usize; points.len()];
+        for _ in 0..points.len() * SAMPLES_PER_POINT {
+            let sample = match optimizer.random_point() {
+                Some(sample) => sample,
+                None => break,
+            };
+
+            if let (_, Some(idx)) = optimizer.nearest(points, sample, None) {
+                for (i, sum) in sums[idx].iter_mut().enumerate() {
+                    let mut offset = sample[i] - points[idx][i];
+                    if let Some(period) = period {
+                        offset -= period[i] * (offset / period[i]).round();
+                    }
+                    *sum += offset;
+                }
+                counts[idx] += 1;
+            }
+        }
+
+        let mut largest_move: Float = 0.0;
+        for (idx, (sum, &count)) in sums.iter().zip(counts.iter()).enumerate() {
+            if count == 0 {
+                continue;
+            }
+
+            let mut centroid = points[idx];
+            for (c, s) in centroid.iter_mut().zip(sum.iter()) {
+                *c += s / count as Float;
+            }
+            let centroid = wrap(centroid, distribution.period());
+            // The centroid of a cell in a non-convex shape can fall outside of it
+            if !distribution.contains(centroid) {
+                continue;
+            }
+
+            largest_move = largest_move.max(optimizer.grid.distance_squared(points[idx], centroid));
+            optimizer.grid.remove(idx, points[idx]);
+            points[idx] = centroid;
+            optimizer.grid.insert(idx, centroid);
+        }
+
+        if largest_move.sqrt() < tolerance {
+            let (min_distance, mean_distance) = optimizer.spacing_of(points);
+
+            return Convergence {
+                iterations: iteration + 1,
+                min_distance,
+                mean_distance,
+                converged: true,
+            };
+        }
+    }
+
+    let (min_distance, mean_distance) = optimizer.spacing_of(points);
+
+    Convergence {
+        iterations,
+        min_distance,
+        mean_distance,
+        converged: false,
+    }
+}
+
+/// The smallest distance between any two of the points
+pub(crate) fn min_distance<const N: usize>(
+    distribution: &Poisson<N>,
+    points: &[Point<N>],
+) -> Float {
+    Optimizer::new(distribution, points).spacing_of(points).0
+}
+
+/// The squared distance from each point to its nearest neighbor
+pub(crate) fn nearest_distances_squared<const N: usize>(
+    distribution: &Poisson<N>,
+    points: &[Point<N>],
+) -> Vec<Float> {
+    let optimizer = Optimizer::new(distribution, points);
+
+    (0..points.len())
+        .map(|idx| optimizer.nearest_squared(points, points[idx], Some(idx)).0)
+        .collect()
+}
+
+/// Estimate the radius of the largest ball, centered in the space, that holds none of the points
+///
+/// We search from around every point for the spot furthest from all of them, so the estimate can
+/// fall a little short but never overshoots. The search has a seed of its own, not the
+/// distribution's, so it's the same every time.
+pub(crate) fn coverage_radius<const N: usize>(
+    distribution: &Poisson<N>,
+    points: &[Point<N>],
+) -> Float {
+    if points.is_empty() {
+        return Float::INFINITY;
+    }
+
+    let mut optimizer = Optimizer::new(distribution, points);
+    optimizer.rng = Rand::seed_from_u64(COVERAGE_SEED);
+    let mut largest: Float = 0.0;
+    for &point in points.iter() {
+        // Every hole is surrounded by points, so searching near each point finds them all
+        let mut best = None;
+        for _ in 0..LOCAL_CANDIDATES {
+            // The largest holes are often at the edges, so don't waste the candidates beyond them
+            let candidate = optimizer.random_point_near(point, optimizer.spacing * 1.5);
+            let candidate = optimizer.clamp(candidate);
+            if !distribution.contains(candidate) {
+                continue;
+            }
+
+            let (dist, _) = optimizer.nearest(points, candidate, None);
+            if !matches!(best, Some((_, best_dist)) if dist <= best_dist) {
+                best = Some((candidate, dist));
+            }
+        }
+
+        if let Some((candidate, dist)) = best {
+            let (mut best, mut best_dist) = optimizer.climb(points, candidate, dist);
+
+            // Stepping straight away from the nearest point stalls once two are equally near, so
+            // finish with random steps, which can slide between them
+            let mut step = best_dist / 4.0;
+            for _ in 0..REFINE_STEPS {
+                let candidate = optimizer.random_point_near(best, step);
+                let candidate = optimizer.clamp(candidate);
+                let dist = if distribution.contains(candidate) {
+                    optimizer.nearest(points, candidate, None).0
+                } else {
+                    0.0
+                };
+                if dist > best_dist {
+                    best = candidate;
+                    best_dist = dist;
+                } else {
+                    step *= 0.9;
+                }
+            }
+
+            largest = largest.max(best_dist);
+        }
+    }
+
+    largest
+}
@@ -0,0 +1,142 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::grid::distance_squared;
+use crate::tests::min_distance;
+use crate::{Float, Point, Poisson, Poisson2D, Shape};
+
+#[test]
+fn raises_min_distance() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(0xF00);
+    let mut points = poisson.generate();
+    let before = min_distance(&points, None);
+
+    let result = poisson.optimize(&mut points, 20);
+
+    assert!(result.min_distance > 1.15 * before);
+    assert!((result.min_distance - min_distance(&points, None)).abs() < 1e-6);
+    assert!(points.iter().all(|&p| poisson.contains(p)));
+}
+
+#[test]
+fn converges_periodic() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_periodic(true).with_seed(12);
+    let mut points = poisson.generate_count(100);
+    let before = min_distance(&points, Some([1.0, 1.0]));
+
+    let result = poisson.optimize(&mut points, 200);
+
+    assert!(result.converged);
+    assert!(result.iterations < 200);
+    // Schlömer reports about 93% of the spacing of a perfect hexagonal packing
+    let hexagonal = (2.0 / (3.0 as Float).sqrt() / 100.0).sqrt();
+    assert!(result.min_distance > 0.85 * hexagonal);
+    assert!(result.min_distance > before);
+    assert!((result.min_distance - min_distance(&points, Some([1.0, 1.0]))).abs() < 1e-6);
+}
+
+#[test]
+fn works_in_higher_dimensions() {
+    let mut poisson = Poisson::<4>::new();
+    poisson
+        .with_shape(
+            Shape::Ball {
+                center: [0.0; 4],
+                radius: 1.0,
+            },
+            0.4,
+        )
+        .with_seed(4);
+    let mut points = poisson.generate();
+    let before = min_distance(&points, None);
+
+    let result = poisson.optimize(&mut points, 5);
+
+    assert!(result.min_distance > before);
+    assert!(points.iter().all(|&p| poisson.contains(p)));
+}
+
+/// The mean squared distance from each point of a fine lattice over the unit box to the nearest
+/// sample, which is the energy Lloyd's relaxation minimizes
+///
+/// Unlike statistics of the distances between the samples themselves, this is hardly affected by
+/// the jitter left over from estimating the cells.
+fn cvt_energy(points: &[Point<2>], period: Option<[Float; 2]>) -> Float {
+    let steps = 200;
+    let mut total = 0.0;
+    for i in 0..steps {
+        for j in 0..steps {
+            let probe = [
+                (i as Float + 0.5) / steps as Float,
+                (j as Float + 0.5) / steps as Float,
+            ];
+            total += points
+                .iter()
+                .map(|&p| distance_squared(probe, p, period))
+                .fold(Float::INFINITY, Float::min);
+        }
+    }
+
+    total / (steps * steps) as Float
+}
+
+#[test]
+fn relaxing_makes_points_regular() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_periodic(true).with_seed(0x11);
+    let mut points = poisson.generate();
+    let before = cvt_energy(&points, Some([1.0, 1.0]));
+
+    let result = poisson.relax(&mut points, 30, 0.0);
+
+    assert!(!result.converged);
+    assert_eq!(result.iterations, 30);
+    let after = cvt_energy(&points, Some([1.0, 1.0]));
+    assert!(after < before * 0.97, "{} -> {}", before, after);
+    assert!(points.iter().all(|&p| poisson.contains(p)));
+}
+
+#[test]
+fn relaxing_stops_at_tolerance() {
+    let mut poisson = Poisson::<3>::new();
+    poisson.with_dimensions([1.0; 3], 0.2).with_seed(5);
+    let mut points = poisson.generate();
+
+    let result = poisson.relax(&mut points, 100, 0.05);
+
+    assert!(result.converged);
+    assert!(result.iterations < 100);
+    assert!(points.iter().all(|&p| poisson.contains(p)));
+}
+
+#[test]
+fn degenerate_spaces_finish() {
+    let line = [[0.1, 0.0], [0.4, 0.0], [0.9, 0.0]];
+
+    let mut flat = Poisson2D::new();
+    flat.with_dimensions([1.0, 0.0], 0.1).with_seed(3);
+    let mut points = line;
+    let result = flat.optimize(&mut points, 5);
+    assert!(result.min_distance.is_finite());
+    let mut points = line;
+    flat.relax(&mut points, 5, 0.0);
+    assert!((crate::analysis::min_distance(&line, &flat) - 0.3).abs() < 1e-6);
+
+    let mut disk = Poisson2D::new();
+    disk.with_shape(
+        Shape::Cylinder {
+            start: [0.5, 0.5],
+            end: [0.5, 0.5],
+            radius: 0.5,
+        },
+        0.1,
+    );
+    assert!((crate::analysis::min_distance(&line, &disk) - 0.3).abs() < 1e-6);
+    crate::analysis::metrics(&line, &disk);
+}
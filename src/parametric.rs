@@ -0,0 +1,257 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Point, Poisson};
+use crate::grid::Buckets;
+use crate::iter::{random_point_in, Rand};
+use crate::vector::{cross, norm, sub};
+use rand::prelude::*;
+use std::iter::FusedIterator;
+
+#[cfg(test)]
+mod tests;
+
+/// How many steps to take along each parameter when surveying the surface to size the grid
+const SURVEY_STEPS: usize = 16;
+
+/// A point on a parametric surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParametricPoint {
+    /// The parameters that map onto the point
+    pub uv: Point<2>,
+    /// The position of the point on the surface
+    pub point: Point<3>,
+}
+
+/// An iterator over the points in a Poisson disk distribution on a parametric surface
+///
+/// Created by [`Poisson::iter_parametric`].
+pub struct ParametricIter<F> {
+    /// The distribution whose space is the surface's parameter domain
+    distribution: Poisson<2>,
+    /// The map from parameters to points on the surface
+    map: F,
+    /// The RNG
+    rng: Rand,
+    /// The step used to estimate the derivatives of the map
+    step: Float,
+    /// The grid stores spatially-oriented samples for fast checking of neighboring sample points
+    grid: Buckets<3>,
+    /// Every point we've generated so far
+    points: Vec<ParametricPoint>,
+    /// A list of valid points that we have not yet visited
+    active: Vec<usize>,
+    /// Whether we've placed the first point yet
+    started: bool,
+}
+
+impl<F> ParametricIter<F>
+where
+    F: Fn(Point<2>) -> Point<3>,
+{
+    /// Create an iterator over the surface
+    pub(crate) fn new(distribution: &Poisson<2>, map: F) -> Self {
+        // We maintain a grid of our samples for faster radius checking, over a box that should
+        // hold most of the surface; any points that stray outside it are kept in its edge cells
+        let radius = distribution.radius;
+        let (bounds, area) = survey(distribution, &map);
+        let expected = if radius > 0.0 {
+            (area / (radius.powi(2) * (3.0 as Float).sqrt() / 2.0)).ceil() as usize
+        } else {
+            1
+        };
+        let grid = Buckets::new(bounds, radius, false, expected);
+
+        let rng = distribution.rng();
+
+        // Central differences are most accurate with a step around the cube root of the epsilon
+        let (_, size) = distribution.bounds();
+        let step = size[0].max(size[1]) * Float::EPSILON.cbrt();
+
+        ParametricIter {
+            distribution: distribution.clone(),
+            map,
+            rng,
+            step,
+            grid,
+            points: Vec::new(),
+            active: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Estimate how far the surface moves for a unit step from `uv` in each parameter
+    fn jacobian(&self, uv: Point<2>) -> [Point<3>; 2] {
+        let mut jacobian = [[0.0; 3]; 2];
+
+        for (axis, column) in jacobian.iter_mut().enumerate() {
+            let mut ahead = uv;
+            let mut behind = uv;
+            ahead[axis] += self.step;
+            behind[axis] -= self.step;
+
+            let (ahead, behind) = ((self.map)(ahead), (self.map)(behind));
+            for i in 0..3 {
+                column[i] = (ahead[i] - behind[i]) / (2.0 * self.step);
+            }
+        }
+
+        jacobian
+    }
+
+    /// Generate a random point whose image lies roughly between `radius` and `2 * radius` away
+    /// from the image of the given point
+    fn generate_random_point(&mut self, around: ParametricPoint) -> Option<Point<2>> {
+        // Pick a random distance away from our point, and a random direction in parameter space
+        let dist = self.distribution.radius * (1.0 + self.rng.gen::<Float>());
+        let angle = self.rng.gen::<Float>() * 2.0 * std::f64::consts::PI as Float;
+        let direction = [angle.cos(), angle.sin()];
+
+        // Scale the step by how fast the surface moves in that direction, so it covers `dist`
+        // on the surface no matter how stretched the parameterization is there
+        let [du, dv] = self.jacobian(around.uv);
+        let speed = (0..3)
+            .map(|i| (du[i] * direction[0] + dv[i] * direction[1]).powi(2))
+            .sum::<Float>()
+            .sqrt();
+        if !speed.is_normal() {
+            // The surface is pinched to a point here, e.g. at the pole of a sphere
+            return None;
+        }
+
+        let step = dist / speed;
+        Some([
+            around.uv[0] + direction[0] * step,
+            around.uv[1] + direction[1] * step,
+        ])
+    }
+
+    /// Returns true if there is at least one other sample point within `radius` of this point on
+    /// the surface
+    fn in_neighborhood(&self, point: Point<3>) -> bool {
+        let r_squared = self.distribution.radius.powi(2);
+
+        let mut found = false;
+        self.grid
+            .for_each_near(point, self.distribution.radius, |idx| {
+                found |= self.grid.distance_squared(point, self.points[idx].point) < r_squared;
+            });
+
+        found
+    }
+
+    /// Try to add the point at `uv`, returning it if it's far enough from the others
+    fn try_point(&mut self, uv: Point<2>) -> Option<ParametricPoint> {
+        if !self.distribution.contains(uv) {
+            return None;
+        }
+
+        let point = (self.map)(uv);
+        if self.in_neighborhood(point) {
+            return None;
+        }
+
+        let sample = ParametricPoint { uv, point };
+        self.grid.insert(self.points.len(), point);
+        self.active.push(self.points.len());
+        self.points.push(sample);
+
+        Some(sample)
+    }
+}
+
+impl<F> Iterator for ParametricIter<F>
+where
+    F: Fn(Point<2>) -> Point<3>,
+{
+    type Item = ParametricPoint;
+
+    fn next(&mut self) -> Option<ParametricPoint> {
+        // Without a positive radius there's no end to the points we could fit
+        let radius = self.distribution.radius;
+        if radius <= 0.0 || radius.is_nan() {
+            return None;
+        }
+        if !self.started {
+            // Start from a random point in the parameter domain
+            self.started = true;
+            let bounds = self.distribution.bounds();
+            for _ in 0..1000 {
+                let uv = random_point_in(&mut self.rng, bounds);
+                if let Some(sample) = self.try_point(uv) {
+                    return Some(sample);
+                }
+            }
+        }
+
+        while !self.active.is_empty() {
+            let i = self.rng.gen_range(0..self.active.len());
+            let around = self.points[self.active[i]];
+
+            for _ in 0..self.distribution.num_samples {
+                let sample = self
+                    .generate_random_point(around)
+                    .and_then(|uv| self.try_point(uv));
+
+                if sample.is_some() {
+                    return sample;
+                }
+            }
+
+            self.active.swap_remove(i);
+        }
+
+        None
+    }
+}
+
+impl<F> FusedIterator for ParametricIter<F> where F: Fn(Point<2>) -> Point<3> {}
+
+/// Estimate the box holding the surface, and its area, from a coarse grid over the parameters
+fn survey<F>(distribution: &Poisson<2>, map: &F) -> ((Point<3>, [Float; 3]), Float)
+where
+    F: Fn(Point<2>) -> Point<3>,
+{
+    let (origin, size) = distribution.bounds();
+    let at = |i: usize, j: usize| {
+        map([
+            origin[0] + size[0] * i as Float / SURVEY_STEPS as Float,
+            origin[1] + size[1] * j as Float / SURVEY_STEPS as Float,
+        ])
+    };
+
+    let mut min = [Float::INFINITY; 3];
+    let mut max = [Float::NEG_INFINITY; 3];
+    let mut area = 0.0;
+    for i in 0..=SURVEY_STEPS {
+        for j in 0..=SURVEY_STEPS {
+            let point = at(i, j);
+            if point.iter().all(|p| p.is_finite()) {
+                for k in 0..3 {
+                    min[k] = min[k].min(point[k]);
+                    max[k] = max[k].max(point[k]);
+                }
+            }
+
+            if i < SURVEY_STEPS && j < SURVEY_STEPS {
+                // Each patch of the grid is roughly a parallelogram
+                let (du, dv) = (sub(at(i + 1, j), point), sub(at(i, j + 1), point));
+                let patch = norm(cross(du, dv));
+                if patch.is_finite() {
+                    area += patch;
+                }
+            }
+        }
+    }
+
+    if min[0] > max[0] {
+        // The map never gave us a usable point
+        return (([0.0; 3], [0.0; 3]), 0.0);
+    }
+
+    ((min, sub(max, min)), area)
+}
@@ -0,0 +1,114 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+use crate::{ActivePolicy, Candidates, Poisson2D, Shape};
+
+const TAU: Float = 2.0 * std::f64::consts::PI as Float;
+
+/// Asserts that no two points on the surface are closer than the radius
+fn assert_spaced(points: &[ParametricPoint], radius: Float) {
+    let points: Vec<_> = points.iter().map(|p| p.point).collect();
+
+    crate::tests::assert_spaced(&points, radius, None);
+}
+
+#[test]
+fn flat_map_matches_plane() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([2.0, 2.0], 0.1).with_seed(1337);
+
+    let points: Vec<_> = poisson.iter_parametric(|[u, v]| [u, v, 0.0]).collect();
+    let plane = poisson.iter().count() as Float;
+
+    assert_spaced(&points, 0.1);
+    assert!((points.len() as Float - plane).abs() < plane * 0.1);
+    for sample in &points {
+        assert_eq!(sample.point, [sample.uv[0], sample.uv[1], 0.0]);
+    }
+}
+
+#[test]
+fn steep_heightfield_is_not_crowded() {
+    // A slope rising 4 units for every 1 across; its surface is about 4 times the area of its
+    // footprint, and a plain 2D distribution would crowd points together along the slope
+    let slope = |[u, v]: Point<2>| [u, v, 4.0 * u];
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([2.0, 2.0], 0.1).with_seed(1337);
+
+    let points: Vec<_> = poisson.iter_parametric(slope).collect();
+    let flat = poisson.iter().count() as Float;
+    assert_spaced(&points, 0.1);
+
+    let ratio = points.len() as Float / flat;
+    let stretch = (17.0 as Float).sqrt();
+    assert!((ratio - stretch).abs() < stretch * 0.1, "ratio {}", ratio);
+}
+
+#[test]
+fn cylinder_wraps_around() {
+    let cylinder = |[u, v]: Point<2>| [u.cos(), u.sin(), v];
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([TAU, 2.0], 0.2).with_seed(42);
+
+    let points: Vec<_> = poisson.iter_parametric(cylinder).collect();
+
+    // Points either side of the seam at u = 0 must keep their distance too
+    assert_spaced(&points, 0.2);
+    assert!(points.iter().any(|p| p.uv[0] < 0.2));
+    assert!(points.iter().any(|p| p.uv[0] > TAU - 0.2));
+}
+
+#[test]
+fn sphere_with_pinched_poles() {
+    let sphere = |[u, v]: Point<2>| [v.sin() * u.cos(), v.sin() * u.sin(), v.cos()];
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_shape(
+            Shape::Cuboid {
+                min: [0.0, 0.0],
+                max: [TAU, TAU / 2.0],
+            },
+            0.2,
+        )
+        .with_seed(7);
+
+    let points: Vec<_> = poisson.iter_parametric(sphere).collect();
+    assert_spaced(&points, 0.2);
+
+    // The poles should be covered, not left empty nor crowded
+    let near_pole = |p: &&ParametricPoint| p.point[2] > 0.95;
+    assert!(points.iter().filter(near_pole).count() >= 1);
+    let upper = points.iter().filter(|p| p.point[2] > 0.0).count() as Float;
+    assert!((upper / points.len() as Float - 0.5).abs() < 0.05);
+}
+
+#[test]
+fn zero_radius_yields_nothing() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([1.0, 1.0], 0.0);
+
+    assert_eq!(poisson.iter_parametric(|[u, v]| [u, v, 0.0]).count(), 0);
+}
+
+#[test]
+fn sampling_settings_are_ignored() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_dimensions([1.0, 1.0], 0.1)
+        .with_seed(0xBADC0FFEE);
+    let plane = |[u, v]: [Float; 2]| [u, v, 0.0];
+    let expected: Vec<_> = poisson.iter_parametric(plane).map(|p| p.uv).collect();
+
+    poisson
+        .with_candidates(Candidates::Boundary)
+        .with_active_policy(ActivePolicy::Fifo)
+        .with_maximal(true);
+    let points: Vec<_> = poisson.iter_parametric(plane).map(|p| p.uv).collect();
+
+    assert_eq!(points, expected);
+}
@@ -0,0 +1,77 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Iter, Point};
+use std::iter::FusedIterator;
+
+#[cfg(test)]
+mod tests;
+
+/// The golden ratio as a 64-bit fraction, which SplitMix64 steps its state by
+pub(crate) const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// A point, numbered in the order it was returned, with a seed of its own
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeededPoint<const N: usize> {
+    /// The index of the point in the iterator's output
+    pub id: usize,
+    /// The position of the point
+    pub point: Point<N>,
+    /// A seed for the point's own random number generator
+    ///
+    /// This depends only on the distribution's seed and the point's `id`.
+    pub seed: u64,
+}
+
+/// An iterator over the points of a distribution, each numbered and with a seed of its own
+///
+/// Created by [`Iter::seeded`].
+pub struct SeededIter<const N: usize> {
+    /// The iterator generating the points
+    iter: Iter<N>,
+    /// The seed each point's seed is derived from
+    seed: u64,
+    /// The id of the next point
+    id: usize,
+}
+
+impl<const N: usize> SeededIter<N> {
+    /// Number the points of the iterator, deriving their seeds from `seed`
+    pub(crate) fn new(iter: Iter<N>, seed: u64) -> Self {
+        SeededIter { iter, seed, id: 0 }
+    }
+}
+
+impl<const N: usize> Iterator for SeededIter<N> {
+    type Item = SeededPoint<N>;
+
+    fn next(&mut self) -> Option<SeededPoint<N>> {
+        let point = self.iter.next()?;
+        let id = self.id;
+        self.id += 1;
+
+        Some(SeededPoint {
+            id,
+            point,
+            seed: point_seed(self.seed, id),
+        })
+    }
+}
+
+impl<const N: usize> FusedIterator for SeededIter<N> {}
+
+/// The seed of the point with the given id
+///
+/// This is the `id`th output of a SplitMix64 generator started from `seed`, so each point's seed
+/// can be found without the ones before it, and seeds of neighboring ids are unrelated.
+pub(crate) fn point_seed(seed: u64, id: usize) -> u64 {
+    let mut z = seed.wrapping_add((id as u64).wrapping_add(1).wrapping_mul(GOLDEN_GAMMA));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+    z ^ (z >> 31)
+}
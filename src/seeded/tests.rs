@@ -0,0 +1,54 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::Poisson2D;
+use std::collections::HashSet;
+
+#[test]
+fn seeded_points_match_plain_iteration() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(0x5EED);
+
+    let plain = poisson.generate();
+    let seeded: Vec<_> = poisson.iter().seeded().collect();
+
+    assert_eq!(seeded.len(), plain.len());
+    for (i, (seeded, &point)) in seeded.iter().zip(plain.iter()).enumerate() {
+        assert_eq!(seeded.id, i);
+        assert_eq!(seeded.point, point);
+    }
+}
+
+#[test]
+fn seeds_are_deterministic() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(0xC0FFEE);
+    let first: Vec<_> = poisson.iter().seeded().collect();
+    let second: Vec<_> = poisson.iter().seeded().collect();
+    assert_eq!(first, second);
+
+    // Every point has its own seed
+    let seeds: HashSet<_> = first.iter().map(|p| p.seed).collect();
+    assert_eq!(seeds.len(), first.len());
+
+    // Another distribution seed gives other point seeds
+    poisson.with_seed(0xDECAF);
+    let other: Vec<_> = poisson.iter().seeded().take(10).collect();
+    assert!(first
+        .iter()
+        .zip(other.iter())
+        .all(|(a, b)| a.seed != b.seed));
+}
+
+#[test]
+fn unseeded_distribution_still_numbers_points() {
+    let poisson = Poisson2D::new();
+    let points: Vec<_> = poisson.iter().seeded().collect();
+
+    assert!(!points.is_empty());
+    assert!(points.iter().enumerate().all(|(i, p)| p.id == i));
+}
@@ -0,0 +1,103 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Iter, Point, Poisson, Shape};
+
+#[cfg(test)]
+mod tests;
+
+/// A finished Poisson disk distribution that can be edited
+///
+/// The set keeps the grid used to generate the distribution, so points can be removed from a
+/// region and the hole filled again, without disturbing the rest of the points.
+///
+/// ```
+/// # use fast_poisson::{Poisson2D, PoissonSet, Shape};
+/// let mut trees = PoissonSet::new(&Poisson2D::new());
+///
+/// // Clear a glade and plant it again
+/// let glade = Shape::Ball {
+///     center: [0.5, 0.5],
+///     radius: 0.2,
+/// };
+/// let felled = trees.remove_within(&glade);
+/// let planted = trees.refill(&glade);
+/// ```
+pub struct PoissonSet<const N: usize> {
+    /// The distribution the points belong to
+    distribution: Poisson<N>,
+    /// The iterator that generated the points, which holds them in its grid
+    iter: Iter<N>,
+}
+
+impl<const N: usize> PoissonSet<N> {
+    /// Generate the whole distribution
+    pub fn new(distribution: &Poisson<N>) -> Self {
+        let mut iter = Iter::new(distribution.clone());
+        iter.by_ref().for_each(drop);
+
+        PoissonSet {
+            distribution: distribution.clone(),
+            iter,
+        }
+    }
+
+    /// The number of points in the set
+    pub fn len(&self) -> usize {
+        self.iter.points().count()
+    }
+
+    /// Returns true if there are no points in the set
+    pub fn is_empty(&self) -> bool {
+        self.iter.points().next().is_none()
+    }
+
+    /// An iterator over the points in the set
+    ///
+    /// The points are in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = Point<N>> + '_ {
+        self.iter.points()
+    }
+
+    /// Collect the points into a [`Vec`]
+    pub fn to_vec(&self) -> Vec<Point<N>> {
+        self.iter.points().collect()
+    }
+
+    /// Add a point to the set, as long as it's within the distribution's space and at least the
+    /// radius away from every other point
+    ///
+    /// Returns true if the point was added.
+    pub fn insert(&mut self, point: Point<N>) -> bool {
+        self.iter.insert(point)
+    }
+
+    /// Remove all of the points inside the region, returning them
+    pub fn remove_within(&mut self, region: &Shape<N>) -> Vec<Point<N>> {
+        self.iter.remove_where(|point| region.contains(point))
+    }
+
+    /// Fill the region with new points, returning them
+    ///
+    /// New points are only placed where they're inside both the region and the distribution's
+    /// space, and at least the radius away from every point, old and new; the points outside
+    /// the region stay put.
+    pub fn refill(&mut self, region: &Shape<N>) -> Vec<Point<N>> {
+        let (min, max) = region.bounds();
+        let mut size = [0.0; N];
+        for (s, (lo, hi)) in size.iter_mut().zip(min.iter().zip(max.iter())) {
+            *s = (hi - lo).max(0.0 as Float);
+        }
+
+        let distribution = self.distribution.clone();
+        let shape = region.clone();
+        self.iter.refill(
+            Box::new(move |point| distribution.contains(point) && shape.contains(point)),
+            (min, size),
+        )
+    }
+}
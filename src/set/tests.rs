@@ -0,0 +1,88 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+use crate::tests::assert_spaced;
+use crate::Poisson2D;
+
+#[test]
+fn matches_generate() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(0xACE);
+    let set = PoissonSet::new(&poisson);
+
+    let mut points = set.to_vec();
+    let mut generated = poisson.generate();
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    generated.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(points, generated);
+    assert_eq!(set.len(), generated.len());
+}
+
+#[test]
+fn remove_and_refill() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_dimensions([2.0, 2.0], 0.05).with_seed(1);
+    let mut set = PoissonSet::new(&poisson);
+    let before = set.len();
+
+    let hole = Shape::Ball {
+        center: [1.0, 1.0],
+        radius: 0.5,
+    };
+    let removed = set.remove_within(&hole);
+    assert!(removed.len() > 50);
+    assert!(removed.iter().all(|&p| hole.contains(p)));
+    assert!(set.iter().all(|p| !hole.contains(p)));
+    let untouched = set.to_vec();
+
+    let added = set.refill(&hole);
+    assert!(added.iter().all(|&p| hole.contains(p)));
+    // About as many points fit in the hole as before
+    assert!(added.len() as f64 > 0.9 * removed.len() as f64);
+    assert_eq!(set.len(), untouched.len() + added.len());
+    assert!(untouched.iter().all(|p| set.iter().any(|q| q == *p)));
+    assert_spaced(&set.to_vec(), 0.05, None);
+    assert!(set.len() as f64 > 0.95 * before as f64);
+}
+
+#[test]
+fn refill_isolated_hole_in_periodic_box() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_periodic(true).with_seed(77);
+    let mut set = PoissonSet::new(&poisson);
+
+    // A hole wider than 2r, straddling nothing but empty space once cleared
+    let hole = Shape::Cuboid {
+        min: [0.0, 0.0],
+        max: [0.6, 0.6],
+    };
+    set.remove_within(&hole);
+    let added = set.refill(&hole);
+
+    assert!(!added.is_empty());
+    assert_spaced(&set.to_vec(), 0.1, Some([1.0, 1.0]));
+}
+
+#[test]
+fn insert_respects_spacing() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_seed(3);
+    let mut set = PoissonSet::new(&poisson);
+
+    let existing = set.iter().next().unwrap();
+    assert!(!set.insert([existing[0] + 0.01, existing[1]]));
+    assert!(!set.insert([1.5, 0.5]));
+
+    let hole = Shape::Ball {
+        center: [0.5, 0.5],
+        radius: 0.3,
+    };
+    set.remove_within(&hole);
+    assert!(set.insert([0.5, 0.5]));
+    assert!(set.iter().any(|p| p == [0.5, 0.5]));
+}
@@ -0,0 +1,418 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Float, Point};
+use crate::grid::distance_squared;
+use crate::vector::{dot, lerp, norm, sub};
+#[cfg(feature = "derive_serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+/// A bounded region of N-dimensional space to fill with a Poisson disk distribution
+///
+/// By default a [`Poisson`](crate::Poisson) fills the box set by
+/// [`with_dimensions`](crate::Poisson::with_dimensions); use
+/// [`with_shape`](crate::Poisson::with_shape) to fill one of these shapes instead.
+///
+/// ```
+/// # use fast_poisson::{Poisson2D, Shape};
+/// let points = Poisson2D::new()
+///     .with_shape(Shape::Ball { center: [0.0, 0.0], radius: 1.0 }, 0.1)
+///     .generate();
+///
+/// assert!(points.iter().all(|[x, y]| x * x + y * y <= 1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "derive_serde", derive(Serialize, Deserialize))]
+pub enum Shape<const N: usize> {
+    /// An axis-aligned box spanning the semi-open range [`min`, `max`) in each dimension
+    Cuboid {
+        /// The lowest corner of the box
+        #[cfg_attr(feature = "derive_serde", serde(with = "serde_arrays"))]
+        min: Point<N>,
+        /// The highest corner of the box
+        #[cfg_attr(feature = "derive_serde", serde(with = "serde_arrays"))]
+        max: Point<N>,
+    },
+    /// An N-ball, i.e. a disk in 2 dimensions or a solid sphere in 3
+    Ball {
+        /// The center of the ball
+        #[cfg_attr(feature = "derive_serde", serde(with = "serde_arrays"))]
+        center: Point<N>,
+        /// The radius of the ball
+        radius: Float,
+    },
+    /// An axis-aligned ellipsoid
+    Ellipsoid {
+        /// The center of the ellipsoid
+        #[cfg_attr(feature = "derive_serde", serde(with = "serde_arrays"))]
+        center: Point<N>,
+        /// The length of the semi-axis along each dimension
+        #[cfg_attr(feature = "derive_serde", serde(with = "serde_arrays"))]
+        semi_axes: [Float; N],
+    },
+    /// An N-simplex, i.e. a triangle in 2 dimensions or a tetrahedron in 3
+    Simplex {
+        /// The N + 1 vertices of the simplex
+        #[cfg_attr(feature = "derive_serde", serde(with = "serde_points"))]
+        vertices: Vec<Point<N>>,
+    },
+    /// A cylinder with flat caps around the segment from `start` to `end`
+    Cylinder {
+        /// The center of one cap
+        #[cfg_attr(feature = "derive_serde", serde(with = "serde_arrays"))]
+        start: Point<N>,
+        /// The center of the other cap
+        #[cfg_attr(feature = "derive_serde", serde(with = "serde_arrays"))]
+        end: Point<N>,
+        /// The radius of the cylinder
+        radius: Float,
+    },
+    /// A capsule, i.e. every point within `radius` of the segment from `start` to `end`
+    Capsule {
+        /// One end of the capsule's core segment
+        #[cfg_attr(feature = "derive_serde", serde(with = "serde_arrays"))]
+        start: Point<N>,
+        /// The other end of the capsule's core segment
+        #[cfg_attr(feature = "derive_serde", serde(with = "serde_arrays"))]
+        end: Point<N>,
+        /// The radius of the capsule
+        radius: Float,
+    },
+}
+
+impl<const N: usize> Shape<N> {
+    /// Returns true if the point lies inside this shape
+    ///
+    /// ```
+    /// # use fast_poisson::Shape;
+    /// let ball = Shape::Ball { center: [0.0; 3], radius: 1.0 };
+    ///
+    /// assert!(ball.contains([0.5, 0.5, 0.5]));
+    /// assert!(!ball.contains([1.0, 1.0, 0.0]));
+    /// ```
+    pub fn contains(&self, point: Point<N>) -> bool {
+        match self {
+            Shape::Cuboid { min, max } => (0..N).all(|i| point[i] >= min[i] && point[i] < max[i]),
+            Shape::Ball { center, radius } => {
+                distance_squared(point, *center, None) <= radius.powi(2)
+            }
+            Shape::Ellipsoid { center, semi_axes } => {
+                (0..N)
+                    .map(|i| ((point[i] - center[i]) / semi_axes[i]).powi(2))
+                    .sum::<Float>()
+                    <= 1.0
+            }
+            Shape::Simplex { vertices } => match barycentric(vertices, point) {
+                Some(weights) => {
+                    weights.iter().all(|&w| w >= 0.0) && weights.iter().sum::<Float>() <= 1.0
+                }
+                None => false,
+            },
+            Shape::Cylinder { start, end, radius } => {
+                let (t, dist_squared) = project_onto_axis(point, *start, *end);
+
+                (0.0..=1.0).contains(&t) && dist_squared <= radius.powi(2)
+            }
+            Shape::Capsule { start, end, radius } => {
+                let (t, _) = project_onto_axis(point, *start, *end);
+                let closest = lerp(*start, *end, t.clamp(0.0, 1.0));
+
+                distance_squared(point, closest, None) <= radius.powi(2)
+            }
+        }
+    }
+
+    /// Returns the lowest and highest corners of the smallest axis-aligned box holding this shape
+    ///
+    /// ```
+    /// # use fast_poisson::Shape;
+    /// let ball = Shape::Ball { center: [1.0, 2.0], radius: 1.0 };
+    ///
+    /// assert_eq!(ball.bounds(), ([0.0, 1.0], [2.0, 3.0]));
+    /// ```
+    pub fn bounds(&self) -> (Point<N>, Point<N>) {
+        let mut min = [0.0; N];
+        let mut max = [0.0; N];
+
+        match self {
+            Shape::Cuboid { min: lo, max: hi } => {
+                min = *lo;
+                max = *hi;
+            }
+            Shape::Ball { center, radius } => {
+                for i in 0..N {
+                    min[i] = center[i] - radius;
+                    max[i] = center[i] + radius;
+                }
+            }
+            Shape::Ellipsoid { center, semi_axes } => {
+                for i in 0..N {
+                    min[i] = center[i] - semi_axes[i].abs();
+                    max[i] = center[i] + semi_axes[i].abs();
+                }
+            }
+            Shape::Simplex { vertices } => {
+                min = [Float::INFINITY; N];
+                max = [Float::NEG_INFINITY; N];
+                for vertex in vertices {
+                    for i in 0..N {
+                        min[i] = min[i].min(vertex[i]);
+                        max[i] = max[i].max(vertex[i]);
+                    }
+                }
+            }
+            Shape::Cylinder { start, end, radius } => {
+                // Each cap is an (N-1)-ball perpendicular to the axis, so it only reaches out
+                // along dimension i as far as the axis is perpendicular to that dimension
+                let axis = sub(*end, *start);
+                let length = norm(axis);
+                for i in 0..N {
+                    let cos = if length > 0.0 { axis[i] / length } else { 0.0 };
+                    let reach = radius * (1.0 - cos.powi(2)).max(0.0).sqrt();
+                    min[i] = start[i].min(end[i]) - reach;
+                    max[i] = start[i].max(end[i]) + reach;
+                }
+            }
+            Shape::Capsule { start, end, radius } => {
+                for i in 0..N {
+                    min[i] = start[i].min(end[i]) - radius;
+                    max[i] = start[i].max(end[i]) + radius;
+                }
+            }
+        }
+
+        (min, max)
+    }
+
+    /// The N-dimensional volume of this shape
+    ///
+    /// ```
+    /// # use fast_poisson::Shape;
+    /// let square = Shape::Cuboid { min: [0.0, 0.0], max: [2.0, 3.0] };
+    ///
+    /// assert_eq!(square.volume(), 6.0);
+    /// ```
+    pub fn volume(&self) -> Float {
+        match self {
+            Shape::Cuboid { min, max } => (0..N).map(|i| max[i] - min[i]).product(),
+            Shape::Ball { radius, .. } => ball_volume(N, *radius),
+            Shape::Ellipsoid { semi_axes, .. } => {
+                ball_volume(N, 1.0) * semi_axes.iter().map(|a| a.abs()).product::<Float>()
+            }
+            Shape::Simplex { vertices } => {
+                if vertices.len() != N + 1 {
+                    return 0.0;
+                }
+
+                let mut edges = [[0.0; N]; N];
+                for (row, edge) in edges.iter_mut().enumerate() {
+                    *edge = sub(vertices[row + 1], vertices[0]);
+                }
+
+                determinant(edges).abs() / (1..=N).map(|n| n as Float).product::<Float>()
+            }
+            Shape::Cylinder { start, end, radius } => {
+                ball_volume(N.saturating_sub(1), *radius) * norm(sub(*end, *start))
+            }
+            Shape::Capsule { start, end, radius } => {
+                ball_volume(N.saturating_sub(1), *radius) * norm(sub(*end, *start))
+                    + ball_volume(N, *radius)
+            }
+        }
+    }
+
+    /// A point that is always inside a well-formed shape
+    pub(crate) fn center(&self) -> Point<N> {
+        match self {
+            Shape::Cuboid { min, max } => lerp(*min, *max, 0.5),
+            Shape::Ball { center, .. } | Shape::Ellipsoid { center, .. } => *center,
+            Shape::Simplex { vertices } => {
+                let mut centroid = [0.0; N];
+                for vertex in vertices {
+                    for i in 0..N {
+                        centroid[i] += vertex[i] / vertices.len() as Float;
+                    }
+                }
+                centroid
+            }
+            Shape::Cylinder { start, end, .. } | Shape::Capsule { start, end, .. } => {
+                lerp(*start, *end, 0.5)
+            }
+        }
+    }
+}
+
+/// The volume of an n-dimensional ball
+pub(crate) fn ball_volume(n: usize, radius: Float) -> Float {
+    // V(0) = 1, V(1) = 2, and V(n) = V(n - 2) * 2π / n for the unit ball
+    let mut volumes = [1.0, 2.0];
+    for k in 2..=n {
+        volumes[k % 2] *= 2.0 * std::f64::consts::PI as Float / k as Float;
+    }
+
+    volumes[n % 2] * radius.powi(n as i32)
+}
+
+/// The fraction of space that equal balls can fill when packed as densely as possible
+///
+/// These are the densest known packings up to 8 dimensions. Beyond that we fall back to the
+/// Minkowski–Hlawka bound of 2^(1 - n), which denser packings are known to beat.
+pub(crate) fn packing_density(n: usize) -> Float {
+    let pi = std::f64::consts::PI as Float;
+
+    match n {
+        0 | 1 => 1.0,
+        2 => pi / (12.0 as Float).sqrt(),
+        3 => pi / (18.0 as Float).sqrt(),
+        4 => pi.powi(2) / 16.0,
+        5 => pi.powi(2) / (15.0 * (2.0 as Float).sqrt()),
+        6 => pi.powi(3) / (48.0 * (3.0 as Float).sqrt()),
+        7 => pi.powi(3) / 105.0,
+        8 => pi.powi(4) / 384.0,
+        _ => (2.0 as Float).powi(1 - n as i32),
+    }
+}
+
+/// The determinant of a square matrix, by Gaussian elimination
+fn determinant<const N: usize>(mut matrix: [[Float; N]; N]) -> Float {
+    let mut det = 1.0;
+
+    for col in 0..N {
+        let pivot = match (col..N).max_by(|&a, &b| {
+            matrix[a][col]
+                .abs()
+                .partial_cmp(&matrix[b][col].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        }) {
+            Some(pivot) => pivot,
+            None => return 0.0,
+        };
+        if matrix[pivot][col] == 0.0 {
+            return 0.0;
+        }
+        if pivot != col {
+            matrix.swap(col, pivot);
+            det = -det;
+        }
+        det *= matrix[col][col];
+
+        let (upper, lower) = matrix.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for row in lower.iter_mut() {
+            let factor = row[col] / pivot_row[col];
+            for (a, b) in row[col..].iter_mut().zip(pivot_row[col..].iter()) {
+                *a -= factor * b;
+            }
+        }
+    }
+
+    det
+}
+
+/// Project a point onto the line through `start` and `end`
+///
+/// Returns the position along the line, where 0 is `start` and 1 is `end`, and the squared
+/// distance from the point to the line.
+fn project_onto_axis<const N: usize>(
+    point: Point<N>,
+    start: Point<N>,
+    end: Point<N>,
+) -> (Float, Float) {
+    let axis = sub(end, start);
+    let length_squared = dot(axis, axis);
+    if length_squared == 0.0 {
+        return (0.0, distance_squared(point, start, None));
+    }
+
+    let offset = sub(point, start);
+    let t = dot(offset, axis) / length_squared;
+
+    (t, distance_squared(point, lerp(start, end, t), None))
+}
+
+/// Barycentric weights of `point` with respect to vertices 1..=N of the simplex
+///
+/// The weight of vertex 0 is one minus the sum of the others. Returns `None` if the simplex is
+/// degenerate or does not have exactly N + 1 vertices.
+fn barycentric<const N: usize>(vertices: &[Point<N>], point: Point<N>) -> Option<[Float; N]> {
+    if vertices.len() != N + 1 {
+        return None;
+    }
+
+    // Solve `edges * weights = point - vertices[0]` by Gaussian elimination, where each column
+    // of `edges` is the edge from vertex 0 to one of the other vertices
+    let mut matrix = [[0.0; N]; N];
+    let mut weights = sub(point, vertices[0]);
+    for row in 0..N {
+        for col in 0..N {
+            matrix[row][col] = vertices[col + 1][row] - vertices[0][row];
+        }
+    }
+
+    for col in 0..N {
+        // Partial pivoting keeps the elimination numerically stable
+        let pivot = (col..N).max_by(|&a, &b| {
+            matrix[a][col]
+                .abs()
+                .partial_cmp(&matrix[b][col].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+        if matrix[pivot][col].abs() <= Float::EPSILON {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        weights.swap(col, pivot);
+
+        let (upper, lower) = matrix.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            for (a, b) in row[col..].iter_mut().zip(pivot_row[col..].iter()) {
+                *a -= factor * b;
+            }
+            weights[col + 1 + offset] -= factor * weights[col];
+        }
+    }
+
+    for col in (0..N).rev() {
+        for k in col + 1..N {
+            weights[col] -= matrix[col][k] * weights[k];
+        }
+        weights[col] /= matrix[col][col];
+    }
+
+    Some(weights)
+}
+
+/// (De)serialize a `Vec` of const generic arrays, which `serde_arrays` doesn't handle directly
+#[cfg(feature = "derive_serde")]
+mod serde_points {
+    use crate::Point;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper<const N: usize>(#[serde(with = "serde_arrays")] Point<N>);
+
+    pub fn serialize<S, const N: usize>(points: &[Point<N>], ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.collect_seq(points.iter().map(|&point| Wrapper(point)))
+    }
+
+    pub fn deserialize<'de, D, const N: usize>(de: D) -> Result<Vec<Point<N>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let points = Vec::<Wrapper<N>>::deserialize(de)?;
+
+        Ok(points.into_iter().map(|Wrapper(point)| point).collect())
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+
+#[test]
+fn cuboid_is_semi_open() {
+    let cuboid = Shape::Cuboid {
+        min: [-1.0, 0.0],
+        max: [1.0, 2.0],
+    };
+
+    assert!(cuboid.contains([-1.0, 0.0]));
+    assert!(cuboid.contains([0.5, 1.5]));
+    assert!(!cuboid.contains([1.0, 1.0]));
+    assert!(!cuboid.contains([0.0, -0.1]));
+    assert_eq!(cuboid.bounds(), ([-1.0, 0.0], [1.0, 2.0]));
+}
+
+#[test]
+fn ball() {
+    let ball = Shape::Ball {
+        center: [1.0, 1.0, 1.0],
+        radius: 0.5,
+    };
+
+    assert!(ball.contains([1.0, 1.0, 1.0]));
+    assert!(ball.contains([1.4, 1.0, 1.0]));
+    assert!(!ball.contains([1.4, 1.4, 1.0]));
+    assert_eq!(ball.bounds(), ([0.5; 3], [1.5; 3]));
+}
+
+#[test]
+fn ellipsoid() {
+    let ellipsoid = Shape::Ellipsoid {
+        center: [0.0, 0.0],
+        semi_axes: [2.0, 1.0],
+    };
+
+    assert!(ellipsoid.contains([1.9, 0.0]));
+    assert!(ellipsoid.contains([0.0, 0.9]));
+    assert!(!ellipsoid.contains([0.0, 1.1]));
+    assert!(!ellipsoid.contains([1.5, 0.9]));
+    assert_eq!(ellipsoid.bounds(), ([-2.0, -1.0], [2.0, 1.0]));
+}
+
+#[test]
+fn simplex() {
+    let triangle = Shape::Simplex {
+        vertices: vec![[0.0, 0.0], [2.0, 0.0], [0.0, 2.0]],
+    };
+
+    assert!(triangle.contains([0.5, 0.5]));
+    assert!(triangle.contains([0.9, 0.9]));
+    assert!(!triangle.contains([1.1, 1.1]));
+    assert!(!triangle.contains([-0.1, 0.5]));
+    assert_eq!(triangle.bounds(), ([0.0, 0.0], [2.0, 2.0]));
+
+    let tetrahedron = Shape::Simplex {
+        vertices: vec![
+            [1.0, 1.0, 1.0],
+            [1.0, -1.0, -1.0],
+            [-1.0, 1.0, -1.0],
+            [-1.0, -1.0, 1.0],
+        ],
+    };
+
+    assert!(tetrahedron.contains([0.0, 0.0, 0.0]));
+    assert!(tetrahedron.contains(tetrahedron.center()));
+    assert!(!tetrahedron.contains([0.9, 0.9, -0.9]));
+}
+
+#[test]
+fn degenerate_simplex_is_empty() {
+    let flat = Shape::Simplex {
+        vertices: vec![[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]],
+    };
+    assert!(!flat.contains([1.0, 1.0]));
+
+    let too_few = Shape::Simplex {
+        vertices: vec![[0.0, 0.0], [1.0, 0.0]],
+    };
+    assert!(!too_few.contains([0.5, 0.0]));
+}
+
+#[test]
+fn cylinder() {
+    let cylinder = Shape::Cylinder {
+        start: [0.0, 0.0, 0.0],
+        end: [0.0, 0.0, 2.0],
+        radius: 1.0,
+    };
+
+    assert!(cylinder.contains([0.9, 0.0, 0.1]));
+    assert!(cylinder.contains([0.0, 0.5, 1.9]));
+    assert!(!cylinder.contains([0.0, 0.0, 2.1]));
+    assert!(!cylinder.contains([0.8, 0.8, 1.0]));
+    assert_eq!(cylinder.bounds(), ([-1.0, -1.0, 0.0], [1.0, 1.0, 2.0]));
+}
+
+#[test]
+fn tilted_cylinder_bounds_are_tight() {
+    let cylinder = Shape::Cylinder {
+        start: [0.0, 0.0],
+        end: [1.0, 1.0],
+        radius: 1.0,
+    };
+    let reach = (0.5 as Float).sqrt();
+    let (min, max) = cylinder.bounds();
+
+    for i in 0..2 {
+        assert!((min[i] + reach).abs() < 1e-6);
+        assert!((max[i] - 1.0 - reach).abs() < 1e-6);
+    }
+    // The corners of the caps lie exactly on the bounds
+    assert!(cylinder.contains([1.0 + reach - 1e-6, 1.0 - reach + 1e-6]));
+}
+
+#[test]
+fn capsule() {
+    let capsule = Shape::Capsule {
+        start: [0.0, 0.0],
+        end: [2.0, 0.0],
+        radius: 0.5,
+    };
+
+    assert!(capsule.contains([1.0, 0.4]));
+    assert!(capsule.contains([2.4, 0.0]));
+    assert!(capsule.contains([-0.3, 0.3]));
+    assert!(!capsule.contains([2.4, 0.4]));
+    assert_eq!(capsule.bounds(), ([-0.5, -0.5], [2.5, 0.5]));
+}
+
+#[test]
+fn center_is_inside() {
+    let shapes = vec![
+        Shape::Cuboid {
+            min: [1.0, 2.0],
+            max: [3.0, 4.0],
+        },
+        Shape::Ball {
+            center: [1.0, 1.0],
+            radius: 1.0,
+        },
+        Shape::Ellipsoid {
+            center: [1.0, 1.0],
+            semi_axes: [1.0, 0.1],
+        },
+        Shape::Simplex {
+            vertices: vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]],
+        },
+        Shape::Cylinder {
+            start: [0.0, 0.0],
+            end: [5.0, 0.0],
+            radius: 0.01,
+        },
+        Shape::Capsule {
+            start: [0.0, 0.0],
+            end: [0.0, 5.0],
+            radius: 0.01,
+        },
+    ];
+
+    for shape in shapes {
+        assert!(shape.contains(shape.center()), "{:?}", shape);
+    }
+}
+
+#[test]
+fn volumes() {
+    let pi = std::f64::consts::PI as Float;
+    let close = |a: Float, b: Float| (a - b).abs() < 1e-4 * b.abs().max(1.0);
+
+    assert!(close(ball_volume(0, 2.0), 1.0));
+    assert!(close(ball_volume(1, 2.0), 4.0));
+    assert!(close(ball_volume(2, 2.0), 4.0 * pi));
+    assert!(close(ball_volume(3, 1.0), 4.0 / 3.0 * pi));
+    assert!(close(ball_volume(4, 1.0), pi * pi / 2.0));
+
+    let ellipsoid = Shape::Ellipsoid {
+        center: [0.0; 3],
+        semi_axes: [1.0, 2.0, 3.0],
+    };
+    assert!(close(ellipsoid.volume(), 8.0 * pi));
+
+    let tetrahedron = Shape::Simplex {
+        vertices: vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ],
+    };
+    assert!(close(tetrahedron.volume(), 1.0 / 6.0));
+
+    let cylinder = Shape::Cylinder {
+        start: [0.0; 3],
+        end: [0.0, 3.0, 4.0],
+        radius: 1.0,
+    };
+    assert!(close(cylinder.volume(), 5.0 * pi));
+
+    let capsule = Shape::Capsule {
+        start: [0.0; 2],
+        end: [0.0, 2.0],
+        radius: 1.0,
+    };
+    assert!(close(capsule.volume(), 4.0 + pi));
+}
+
+#[test]
+fn packing_densities_shrink() {
+    assert_eq!(packing_density(1), 1.0);
+    assert!((packing_density(2) - 0.9069).abs() < 1e-4);
+    assert!((packing_density(3) - 0.7405).abs() < 1e-4);
+    for n in 1..12 {
+        assert!(packing_density(n + 1) < packing_density(n));
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Sorting grid cells along space-filling curves
+//!
+//! Rather than computing each cell's index along the curve, which for many dimensions would
+//! overflow any integer type, cells are compared directly, after Chan's ["Closest-point problems
+//! simplified on the RAM"][Chan]: the index is the cell's coordinates with their bits interleaved,
+//! so the dimension with the highest differing bit decides the order.
+//!
+//! [Chan]: https://dl.acm.org/doi/10.5555/545381.545490
+
+use crate::iter::Cell;
+use std::cmp::Ordering;
+
+#[cfg(test)]
+mod tests;
+
+/// Sort the cells along a Z-order (Morton) curve, returning their indices in order
+///
+/// The cells' coordinates must not be negative.
+pub(crate) fn morton_order<const N: usize>(cells: &[Cell<N>]) -> Vec<usize> {
+    let keys: Vec<[usize; N]> = cells.iter().map(|&cell| to_unsigned(cell)).collect();
+
+    sort_by_key(&keys)
+}
+
+/// Sort the cells along a Hilbert curve, returning their indices in order
+///
+/// The cells' coordinates must not be negative.
+pub(crate) fn hilbert_order<const N: usize>(cells: &[Cell<N>]) -> Vec<usize> {
+    let highest = cells
+        .iter()
+        .flat_map(|cell| cell.iter())
+        .map(|&c| c.max(0) as usize)
+        .max()
+        .unwrap_or(0);
+    let bits = (8 * std::mem::size_of::<usize>() as u32 - highest.leading_zeros()).max(1);
+
+    let keys: Vec<[usize; N]> = cells
+        .iter()
+        .map(|&cell| hilbert_transpose(to_unsigned(cell), bits))
+        .collect();
+
+    sort_by_key(&keys)
+}
+
+fn to_unsigned<const N: usize>(cell: Cell<N>) -> [usize; N] {
+    let mut unsigned = [0; N];
+    for (u, c) in unsigned.iter_mut().zip(cell.iter()) {
+        *u = (*c).max(0) as usize;
+    }
+
+    unsigned
+}
+
+/// Sort the indices of the keys by the keys' interleaved bits
+fn sort_by_key<const N: usize>(keys: &[[usize; N]]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    order.sort_by(|&a, &b| compare_interleaved(&keys[a], &keys[b]));
+
+    order
+}
+
+/// Compare two keys as if their bits were interleaved, the first dimension's highest bit first
+fn compare_interleaved<const N: usize>(a: &[usize; N], b: &[usize; N]) -> Ordering {
+    // Find the dimension whose highest differing bit is the highest of all; in a tie, the first
+    // dimension's bit comes first
+    let mut dim = 0;
+    let mut highest = 0;
+    for i in 0..N {
+        let differing = a[i] ^ b[i];
+        if highest < differing && highest < (highest ^ differing) {
+            dim = i;
+            highest = differing;
+        }
+    }
+
+    a[dim].cmp(&b[dim])
+}
+
+/// Transform coordinates into the "transposed" Hilbert index, whose interleaved bits are the
+/// index along the curve
+///
+/// This is John Skilling's algorithm from ["Programming the Hilbert curve"][Skilling], for
+/// coordinates of `bits` bits each.
+///
+/// [Skilling]: https://doi.org/10.1063/1.1751381
+fn hilbert_transpose<const N: usize>(mut x: [usize; N], bits: u32) -> [usize; N] {
+    if N == 0 {
+        return x;
+    }
+    let highest = 1 << (bits - 1);
+
+    // Inverse undo
+    let mut q = highest;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..N {
+            if x[i] & q != 0 {
+                // Invert
+                x[0] ^= p;
+            } else {
+                // Exchange
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // Gray encode
+    for i in 1..N {
+        x[i] ^= x[i - 1];
+    }
+    let mut t = 0;
+    let mut q = highest;
+    while q > 1 {
+        if x[N - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for c in x.iter_mut() {
+        *c ^= t;
+    }
+
+    x
+}
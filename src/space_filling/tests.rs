@@ -0,0 +1,75 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+
+/// Every cell of a square grid of the given size
+fn grid(size: isize) -> Vec<Cell<2>> {
+    (0..size)
+        .flat_map(|x| (0..size).map(move |y| [x, y]))
+        .collect()
+}
+
+#[test]
+fn morton_matches_interleaved_bits() {
+    let cells = grid(8);
+    let order = morton_order(&cells);
+
+    // The index along the curve, with the first dimension's bit above the second's
+    let index = |cell: Cell<2>| {
+        (0..3).fold(0, |acc, bit| {
+            acc | ((cell[0] >> bit) & 1) << (2 * bit + 1) | ((cell[1] >> bit) & 1) << (2 * bit)
+        })
+    };
+    for (position, &idx) in order.iter().enumerate() {
+        assert_eq!(index(cells[idx]), position as isize);
+    }
+}
+
+#[test]
+fn hilbert_steps_between_neighbors() {
+    for &size in [2, 4, 16, 32].iter() {
+        let cells = grid(size);
+        let order = hilbert_order(&cells);
+        assert_eq!(order.len(), cells.len());
+
+        // Every step along the curve moves to an adjacent cell
+        for pair in order.windows(2) {
+            let (a, b) = (cells[pair[0]], cells[pair[1]]);
+            assert_eq!(
+                (a[0] - b[0]).abs() + (a[1] - b[1]).abs(),
+                1,
+                "{:?} {:?}",
+                a,
+                b
+            );
+        }
+        // And it starts in a corner
+        assert_eq!(cells[order[0]], [0, 0]);
+    }
+}
+
+#[test]
+fn hilbert_in_three_dimensions() {
+    let cells: Vec<Cell<3>> = (0..8)
+        .flat_map(|x| (0..8).flat_map(move |y| (0..8).map(move |z| [x, y, z])))
+        .collect();
+    let order = hilbert_order(&cells);
+
+    for pair in order.windows(2) {
+        let (a, b) = (cells[pair[0]], cells[pair[1]]);
+        let steps: isize = (0..3).map(|i| (a[i] - b[i]).abs()).sum();
+        assert_eq!(steps, 1, "{:?} {:?}", a, b);
+    }
+}
+
+#[test]
+fn handles_empty_and_single_cells() {
+    assert!(morton_order::<2>(&[]).is_empty());
+    assert!(hilbert_order::<2>(&[]).is_empty());
+    assert_eq!(hilbert_order(&[[0, 0]]), vec![0]);
+}
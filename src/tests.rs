@@ -6,6 +6,65 @@
 // copied, modified, or distributed except according to those terms.
 
 use super::*;
+use crate::grid::distance_squared;
+
+/// The smallest distance between any two of the points, the shortest way around a periodic box
+pub(crate) fn min_distance<const N: usize>(
+    points: &[Point<N>],
+    period: Option<[Float; N]>,
+) -> Float {
+    let mut min = Float::INFINITY;
+    for (i, &a) in points.iter().enumerate() {
+        for &b in &points[i + 1..] {
+            min = min.min(distance_squared(a, b, period));
+        }
+    }
+
+    min.sqrt()
+}
+
+/// The largest distance from any point of a fine lattice over the box to its nearest sample
+pub(crate) fn largest_gap(
+    points: &[Point<2>],
+    size: [Float; 2],
+    period: Option<[Float; 2]>,
+) -> Float {
+    let steps = 200;
+    let mut largest: Float = 0.0;
+    for i in 0..=steps {
+        for j in 0..=steps {
+            let probe = [
+                size[0] * i as Float / steps as Float,
+                size[1] * j as Float / steps as Float,
+            ];
+            let nearest = points
+                .iter()
+                .map(|&p| distance_squared(probe, p, period))
+                .fold(Float::INFINITY, Float::min);
+            largest = largest.max(nearest);
+        }
+    }
+
+    largest.sqrt()
+}
+
+/// Asserts that no two points are closer than `radius`, the shortest way around a periodic box
+pub(crate) fn assert_spaced<const N: usize>(
+    points: &[Point<N>],
+    radius: Float,
+    period: Option<[Float; N]>,
+) {
+    for (i, &a) in points.iter().enumerate() {
+        for &b in &points[i + 1..] {
+            assert!(
+                distance_squared(a, b, period) >= radius * radius,
+                "{:?} and {:?} are too close",
+                a,
+                b
+            );
+        }
+    }
+}
 
 #[test]
 fn new_is_default() {
@@ -101,3 +160,82 @@ fn poisson_equality() {
     // Different dimension, unequal again
     assert_ne!(poisson, poisson2);
 }
+
+#[test]
+fn points_are_spaced() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_dimensions([3.0, 2.0], 0.1)
+        .with_seed(0xBADBEEF);
+    let points = poisson.generate();
+
+    assert_spaced(&points, 0.1, None);
+}
+
+#[test]
+fn fills_shapes() {
+    let shapes = vec![
+        Shape::Ball {
+            center: [-1.0, 2.0, 0.0],
+            radius: 1.0,
+        },
+        Shape::Ellipsoid {
+            center: [0.0; 3],
+            semi_axes: [2.0, 1.0, 0.5],
+        },
+        Shape::Simplex {
+            vertices: vec![
+                [0.0, 0.0, 0.0],
+                [2.0, 0.0, 0.0],
+                [0.0, 2.0, 0.0],
+                [0.0, 0.0, 2.0],
+            ],
+        },
+        Shape::Cylinder {
+            start: [0.0; 3],
+            end: [1.0, 1.0, 1.0],
+            radius: 0.5,
+        },
+        Shape::Capsule {
+            start: [0.0; 3],
+            end: [1.0, -1.0, 0.0],
+            radius: 0.5,
+        },
+    ];
+
+    for shape in shapes {
+        let mut poisson = Poisson3D::new();
+        poisson.with_shape(shape.clone(), 0.2).with_seed(1337);
+        let points = poisson.generate();
+
+        assert!(points.len() > 10, "{:?}", shape);
+        assert!(points.iter().all(|&p| shape.contains(p)), "{:?}", shape);
+        assert_spaced(&points, 0.2, None);
+    }
+}
+
+#[test]
+#[should_panic]
+fn simplex_needs_n_plus_one_vertices() {
+    Poisson2D::new().with_shape(
+        Shape::Simplex {
+            vertices: vec![[0.0, 0.0], [1.0, 0.0]],
+        },
+        0.1,
+    );
+}
+
+#[test]
+fn with_dimensions_replaces_shape() {
+    let mut poisson = Poisson2D::new();
+    poisson.with_shape(
+        Shape::Ball {
+            center: [0.0, 0.0],
+            radius: 1.0,
+        },
+        0.1,
+    );
+    poisson.with_dimensions([1.0, 1.0], 0.1);
+
+    assert!(poisson.iter().all(|[x, y]| x >= 0.0 && y >= 0.0));
+}
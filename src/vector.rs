@@ -0,0 +1,74 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Small vector helpers shared by the shapes, meshes and curves
+//!
+//! Distances between points come from [`grid::distance_squared`](crate::grid::distance_squared),
+//! which also handles periodic boxes.
+
+use super::{Float, Point};
+use crate::grid::distance_squared;
+
+#[cfg(test)]
+mod tests;
+
+/// Component-wise difference `a - b`
+pub(crate) fn sub<const N: usize>(a: Point<N>, b: Point<N>) -> Point<N> {
+    let mut diff = [0.0; N];
+    for i in 0..N {
+        diff[i] = a[i] - b[i];
+    }
+
+    diff
+}
+
+/// The dot product of two vectors
+pub(crate) fn dot<const N: usize>(a: Point<N>, b: Point<N>) -> Float {
+    a.iter().zip(b.iter()).map(|(a, b)| a * b).sum()
+}
+
+/// The cross product of two vectors
+pub(crate) fn cross(a: Point<3>, b: Point<3>) -> Point<3> {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Euclidean length of a vector
+pub(crate) fn norm<const N: usize>(v: Point<N>) -> Float {
+    dot(v, v).sqrt()
+}
+
+/// Scale a vector to unit length, leaving zero vectors alone
+pub(crate) fn normalize<const N: usize>(mut v: Point<N>) -> Point<N> {
+    let length = norm(v);
+
+    if length > 0.0 {
+        for x in v.iter_mut() {
+            *x /= length;
+        }
+    }
+
+    v
+}
+
+/// Euclidean distance between two points
+pub(crate) fn distance<const N: usize>(a: Point<N>, b: Point<N>) -> Float {
+    distance_squared(a, b, None).sqrt()
+}
+
+/// Linear interpolation from `a` (at `t = 0`) to `b` (at `t = 1`)
+pub(crate) fn lerp<const N: usize>(a: Point<N>, b: Point<N>, t: Float) -> Point<N> {
+    let mut point = [0.0; N];
+    for i in 0..N {
+        point[i] = a[i] + (b[i] - a[i]) * t;
+    }
+
+    point
+}
@@ -0,0 +1,36 @@
+// Copyright 2021 Travis Veazey
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// https://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// https://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::*;
+
+#[test]
+fn products() {
+    let (a, b) = ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+
+    assert_eq!(dot(a, b), 0.0);
+    assert_eq!(dot([1.0, 2.0, 3.0], [4.0, 5.0, 6.0]), 32.0);
+    assert_eq!(cross(a, b), [0.0, 0.0, 1.0]);
+    assert_eq!(cross(b, a), [0.0, 0.0, -1.0]);
+}
+
+#[test]
+fn lengths_and_distances() {
+    assert_eq!(sub([3.0, 5.0], [1.0, 1.0]), [2.0, 4.0]);
+    assert_eq!(norm([3.0, 4.0]), 5.0);
+    assert_eq!(distance([1.0, 1.0], [4.0, 5.0]), 5.0);
+    assert_eq!(normalize([0.0, 3.0, 4.0]), [0.0, 0.6, 0.8]);
+    assert_eq!(normalize([0.0; 3]), [0.0; 3]);
+}
+
+#[test]
+fn interpolation() {
+    let (a, b) = ([0.0, 2.0], [4.0, -2.0]);
+
+    assert_eq!(lerp(a, b, 0.0), a);
+    assert_eq!(lerp(a, b, 1.0), b);
+    assert_eq!(lerp(a, b, 0.25), [1.0, 1.0]);
+}
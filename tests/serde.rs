@@ -1,7 +1,6 @@
 #![cfg(feature = "derive_serde")]
 
-use fast_poisson::Poisson2D;
-use serde_json;
+use fast_poisson::{Poisson2D, Shape};
 
 #[test]
 fn serialize_and_deserialize() {
@@ -14,3 +13,21 @@ fn serialize_and_deserialize() {
 
     assert_eq!(poisson, decoded);
 }
+
+#[test]
+fn serialize_and_deserialize_shape() {
+    let mut poisson = Poisson2D::new();
+    poisson
+        .with_shape(
+            Shape::Simplex {
+                vertices: vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]],
+            },
+            0.1,
+        )
+        .with_seed(1337);
+
+    let json = serde_json::to_string(&poisson).unwrap();
+    let decoded = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(poisson, decoded);
+}